@@ -2,29 +2,41 @@
 //! as an underlying field. You should always use RcBot.
 
 use objects;
-use functions::FunctionGetMe;
+use functions::{FunctionAnswerPreCheckoutQuery, FunctionDeleteWebhook, FunctionGetFile,
+                FunctionGetMe, FunctionMessage, FunctionSetMyCommands, FunctionSetWebhook,
+                WrapperSetWebhook};
 use failure::{Error, Fail, ResultExt};
 use error::{ErrorKind, TelegramError};
 use file::File;
+use menu::{self, Menu};
+use multipart;
 
 use std::str;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
 use std::time::Duration;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::rc::Rc;
 use std::cell::{Cell, RefCell};
 
-use tokio_core::reactor::{Core, Handle, Interval};
-use hyper::{Body, Client, Method, Request, Uri};
+use tokio_core::reactor::{Core, Handle, Interval, Timeout};
+use hyper::{Body, Chunk, Client, Method, Request, Uri};
 use hyper::client::{Config, FutureResponse, HttpConnector};
 use hyper::header::ContentType;
 use hyper_tls::HttpsConnector;
-use hyper_multipart::client::multipart;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 use serde_json;
 use serde_json::value::Value;
+use base64;
 use futures::{stream, Future, IntoFuture, Stream};
 use futures::sync::mpsc;
 use futures::sync::mpsc::UnboundedSender;
 
+/// The time in seconds in which a pre-checkout query has to be answered
+pub const PRE_CHECKOUT_DEADLINE: u64 = 10;
+
 /// A clonable, single threaded bot
 ///
 /// The outer API gets implemented on RcBot
@@ -44,6 +56,8 @@ impl RcBot {
 /// The main bot structure
 pub struct Bot {
     pub key: String,
+    pub api_url: RefCell<String>,
+    pub download_limit: Cell<u64>,
     pub name: RefCell<Option<String>>,
     pub handle: Handle,
     pub last_id: Cell<u32>,
@@ -51,6 +65,16 @@ pub struct Bot {
     pub timeout: Cell<u64>,
     pub handlers: RefCell<HashMap<String, UnboundedSender<(RcBot, objects::Message)>>>,
     pub unknown_handler: RefCell<Option<UnboundedSender<(RcBot, objects::Message)>>>,
+    pub commands: RefCell<BTreeMap<String, String>>,
+    pub menus: RefCell<HashMap<String, (Menu, UnboundedSender<(RcBot, objects::CallbackQuery, String)>)>>,
+    pub publish_commands: Cell<bool>,
+    pub poll_handler: RefCell<Option<UnboundedSender<(RcBot, objects::Poll)>>>,
+    pub poll_answer_handler: RefCell<Option<UnboundedSender<(RcBot, objects::PollAnswer)>>>,
+    pub shipping_query_handler: RefCell<Option<UnboundedSender<(RcBot, objects::ShippingQuery)>>>,
+    pub pre_checkout_query_handler:
+        RefCell<Option<UnboundedSender<(RcBot, objects::PreCheckoutQuery)>>>,
+    pub allowed_updates: RefCell<Option<Vec<String>>>,
+    pub raw_updates: Cell<bool>,
 }
 
 impl Bot {
@@ -60,12 +84,23 @@ impl Bot {
         Bot {
             handle: handle.clone(),
             key: key.into(),
+            api_url: RefCell::new("https://api.telegram.org".into()),
+            download_limit: Cell::new(20 * 1024 * 1024),
             name: RefCell::new(None),
             last_id: Cell::new(0),
             update_interval: Cell::new(1000),
             timeout: Cell::new(30),
             handlers: RefCell::new(HashMap::new()),
             unknown_handler: RefCell::new(None),
+            commands: RefCell::new(BTreeMap::new()),
+            menus: RefCell::new(HashMap::new()),
+            publish_commands: Cell::new(false),
+            poll_handler: RefCell::new(None),
+            poll_answer_handler: RefCell::new(None),
+            shipping_query_handler: RefCell::new(None),
+            pre_checkout_query_handler: RefCell::new(None),
+            allowed_updates: RefCell::new(None),
+            raw_updates: Cell::new(false),
         }
     }
 
@@ -94,11 +129,9 @@ impl Bot {
         msg: String,
     ) -> Result<(Client<HttpsConnector<HttpConnector>, Body>, Request<Body>), Error> {
         let url: Result<Uri, _> =
-            format!("https://api.telegram.org/bot{}/{}", self.key, func).parse();
+            format!("{}/bot{}/{}", self.api_url.borrow(), self.key, func).parse();
 
-        let client = Client::configure()
-            .connector(HttpsConnector::new(2, &self.handle).context(ErrorKind::HttpsInitializeError)?)
-            .build(&self.handle);
+        let client = self.build_client()?;
 
         let mut req = Request::new(Method::Post, url.context(ErrorKind::Uri)?);
         req.headers_mut().set(ContentType::json());
@@ -107,32 +140,30 @@ impl Bot {
         Ok((client, req))
     }
 
-    /// Creates a new request with some byte content (e.g. a file). The method properties have to be
-    /// in the formdata setup and cannot be sent as JSON.
+    /// Creates a new request with some byte content (e.g. files). The method properties have to be
+    /// in the formdata setup and cannot be sent as JSON. Each file is sent in the field of its name.
     pub fn fetch_formdata(
         &self,
         func: &'static str,
         msg: &Value,
-        file: File,
-        kind: &str,
+        files: Vec<(String, File)>,
     ) -> impl Future<Item = String, Error = Error> {
         debug!("Send formdata: {}", msg.to_string());
 
-        let request = self.build_formdata(func, msg, file, kind);
+        let request = self.build_formdata(func, msg, files);
 
         request
             .into_future()
             .and_then(|(client, request)| _fetch(client.request(request)))
     }
 
-    /// Builds the HTTP header for a formdata request. The file content is read and then append to
-    /// the formdata. Each key-value pair has a own line.
+    /// Builds the HTTP header for a formdata request. The file content is streamed while the
+    /// request is sent. Each key-value pair has a own line.
     fn build_formdata(
         &self,
         func: &'static str,
         msg: &Value,
-        file: File,
-        kind: &str,
+        files: Vec<(String, File)>,
     ) -> Result<
         (
             Client<HttpsConnector<HttpConnector>, multipart::Body>,
@@ -147,10 +178,10 @@ impl Bot {
             .build(&self.handle);
 
         let url: Result<Uri, _> =
-            format!("https://api.telegram.org/bot{}/{}", self.key, func).parse();
+            format!("{}/bot{}/{}", self.api_url.borrow(), self.key, func).parse();
 
         let mut req = Request::new(Method::Post, url.context(ErrorKind::Uri)?);
-        let mut form = multipart::Form::default();
+        let mut form = multipart::Form::new();
 
         let msg = msg.as_object().ok_or(ErrorKind::JsonNotMap)?;
 
@@ -164,21 +195,56 @@ impl Bot {
             form.add_text(key.as_ref(), val.as_ref());
         }
 
-        match file {
-            File::Memory { name, source } => {
-                form.add_reader_file(kind, source, name);
-            }
-            File::Disk { path } => {
-                form.add_file(kind, path).context(ErrorKind::NoFile)?;
-            }
+        for (name, file) in files {
+            form.add_file(&name, file)?;
         }
-
         form.set_body(&mut req);
 
         Ok((client, req))
     }
 }
 
+impl Bot {
+    /// Creates a new HTTPS client
+    fn build_client(&self) -> Result<Client<HttpsConnector<HttpConnector>, Body>, Error> {
+        Ok(Client::configure()
+            .connector(HttpsConnector::new(2, &self.handle).context(ErrorKind::HttpsInitializeError)?)
+            .build(&self.handle))
+    }
+
+    /// Starts the download of a file. The file path is returned by getFile and the body of the
+    /// response is returned as soon as the header is received.
+    pub fn fetch_file(&self, file_path: &str) -> impl Future<Item = Body, Error = Error> {
+        let url: Result<Uri, _> =
+            format!("{}/file/bot{}/{}", self.api_url.borrow(), self.key, file_path).parse();
+
+        let request = self.build_client()
+            .and_then(|client| Ok((client, url.context(ErrorKind::Uri)?)));
+
+        request
+            .into_future()
+            .and_then(|(client, url)| {
+                client
+                    .get(url)
+                    .map_err(|e| Error::from(e.context(ErrorKind::Hyper)))
+            })
+            .and_then(|res| {
+                if res.status().is_success() {
+                    Ok(res.body())
+                } else {
+                    Err(Error::from(ErrorKind::Download))
+                }
+            })
+    }
+}
+
+/// Decodes a base64url encoded JSON payload of a deep link
+fn decode_payload<T: DeserializeOwned>(payload: &str) -> Result<T, Error> {
+    let json = base64::decode_config(payload, base64::URL_SAFE_NO_PAD).context(ErrorKind::JsonParse)?;
+
+    Ok(serde_json::from_slice(&json).context(ErrorKind::JsonParse)?)
+}
+
 /// Calls the Telegram API for the function and awaits the result. The result is then converted
 /// to a String and returned in a Future.
 pub fn _fetch(fut_res: FutureResponse) -> impl Future<Item = String, Error = Error> {
@@ -222,6 +288,20 @@ impl RcBot {
         self
     }
 
+    /// Sets the base url of the Bot API, e.g. for a local Bot API server
+    pub fn api_url(self, url: &str) -> RcBot {
+        self.inner.api_url.replace(url.trim_right_matches('/').into());
+
+        self
+    }
+
+    /// Sets the maximum size of downloaded files in bytes
+    pub fn download_limit(self, limit: u64) -> RcBot {
+        self.inner.download_limit.set(limit);
+
+        self
+    }
+
     /// Sets the timeout interval for long polling
     pub fn timeout(self, timeout: u64) -> RcBot {
         self.inner.timeout.set(timeout);
@@ -229,10 +309,37 @@ impl RcBot {
         self
     }
 
+    /// Requests only the given update types (e.g. message, inline_query) from Telegram, instead of
+    /// the types derived from the registered handlers
+    pub fn allowed_updates(self, updates: &[&str]) -> RcBot {
+        self.inner
+            .allowed_updates
+            .replace(Some(updates.iter().map(|x| x.to_string()).collect()));
+
+        self
+    }
+
+    /// Publishes all described commands with setMyCommands when the bot is started
+    pub fn publish_commands(self, publish: bool) -> RcBot {
+        self.inner.publish_commands.set(publish);
+
+        self
+    }
+
     /// Creates a new command and returns a stream which will yield a message when the command is send
     pub fn new_cmd(
         &self,
         cmd: &str,
+    ) -> impl Stream<Item = (RcBot, objects::Message), Error = Error> {
+        self.new_cmd_with_description(cmd, "")
+    }
+
+    /// Creates a new command with a description, which is listed by the help command and published
+    /// to Telegram with setMyCommands
+    pub fn new_cmd_with_description(
+        &self,
+        cmd: &str,
+        description: &str,
     ) -> impl Stream<Item = (RcBot, objects::Message), Error = Error> {
         let (sender, receiver) = mpsc::unbounded();
 
@@ -242,11 +349,115 @@ impl RcBot {
             format!("/{}", cmd)
         };
 
-        self.inner.handlers.borrow_mut().insert(cmd.into(), sender);
+        self.inner
+            .commands
+            .borrow_mut()
+            .insert(cmd.clone(), description.into());
+        self.inner.handlers.borrow_mut().insert(cmd, sender);
 
         receiver.map_err(|_| Error::from(ErrorKind::Channel))
     }
 
+    /// Returns all registered commands with their descriptions as BotCommand objects. Commands
+    /// without a description are skipped, because Telegram refuses them.
+    pub fn bot_commands(&self) -> Vec<objects::BotCommand> {
+        self.inner
+            .commands
+            .borrow()
+            .iter()
+            .filter(|&(_, description)| !description.is_empty())
+            .map(|(cmd, description)| {
+                objects::BotCommand::new(cmd.trim_left_matches('/').into(), description.clone())
+            })
+            .collect()
+    }
+
+    /// Generates a help text which lists every registered command accepted by the filter, one
+    /// command per line
+    pub fn help_text<F>(&self, filter: F) -> String
+    where
+        F: Fn(&str) -> bool,
+    {
+        self.inner
+            .commands
+            .borrow()
+            .iter()
+            .filter(|&(cmd, _)| filter(cmd))
+            .map(|(cmd, description)| {
+                if description.is_empty() {
+                    cmd.clone()
+                } else {
+                    format!("{} - {}", cmd, description)
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Registers a "/help" command which replies with the generated help text. The filter is
+    /// called with the received message and the name of each command, so the listed commands can
+    /// be scoped (e.g. hide admin commands in private chats).
+    pub fn help_cmd<F>(&self, filter: F) -> impl Stream<Item = (RcBot, objects::Message), Error = Error>
+    where
+        F: Fn(&objects::Message, &str) -> bool + 'static,
+    {
+        self.new_cmd_with_description("/help", "Shows this help")
+            .and_then(move |(bot, msg)| {
+                let text = bot.help_text(|cmd| filter(&msg, cmd));
+
+                bot.message(msg.chat.id, text).send()
+            })
+    }
+
+    /// Returns a stream which will yield a message when the "/start" command is received. Deep
+    /// links carry a base64url encoded JSON payload, which is decoded to the requested type. If the
+    /// payload is missing or can't be decoded, None is returned.
+    pub fn on_start<T>(
+        &self,
+    ) -> impl Stream<Item = (RcBot, objects::Message, Option<T>), Error = Error>
+    where
+        T: DeserializeOwned + 'static,
+    {
+        self.new_cmd("/start").map(|(bot, msg)| {
+            let payload = msg.text
+                .as_ref()
+                .filter(|text| !text.is_empty())
+                .and_then(|text| match decode_payload(text) {
+                    Ok(payload) => Some(payload),
+                    Err(e) => {
+                        warn!("Could not decode start payload {}: {}", text, e);
+                        None
+                    }
+                });
+
+            (bot, msg, payload)
+        })
+    }
+
+    /// Creates a deep link of the form https://t.me/<botname>?start=<payload>. The payload is
+    /// serialized to JSON and encoded with base64url. This requires the name of the bot, which
+    /// is resolved in `run`.
+    pub fn start_link<T>(&self, payload: &T) -> Result<String, Error>
+    where
+        T: Serialize,
+    {
+        let name = self.inner.name.borrow();
+        let name = name.as_ref().ok_or(ErrorKind::NoBotName)?;
+
+        let json = serde_json::to_vec(payload).context(ErrorKind::JsonSerialize)?;
+        let payload = base64::encode_config(&json, base64::URL_SAFE_NO_PAD);
+
+        if payload.len() > 64 {
+            return Err(Error::from(ErrorKind::PayloadTooLong));
+        }
+
+        Ok(format!(
+            "https://t.me/{}?start={}",
+            name.trim_left_matches('@'),
+            payload
+        ))
+    }
+
     /// Returns a stream which will yield a message when none of previously registered commands matches
     pub fn unknown_cmd(&self) -> impl Stream<Item = (RcBot, objects::Message), Error = Error> {
         let (sender, receiver) = mpsc::unbounded();
@@ -256,6 +467,260 @@ impl RcBot {
         receiver.then(|x| x.map_err(|_| Error::from(ErrorKind::Channel)))
     }
 
+    /// Returns a stream which will yield the new state of a poll, when it is changed. Only polls
+    /// which are sent by the bot and stopped polls are reported.
+    pub fn on_poll(&self) -> impl Stream<Item = (RcBot, objects::Poll), Error = Error> {
+        let (sender, receiver) = mpsc::unbounded();
+
+        *self.inner.poll_handler.borrow_mut() = Some(sender);
+
+        receiver.map_err(|_| Error::from(ErrorKind::Channel))
+    }
+
+    /// Returns a stream which will yield the answer of a user, when they vote in a non-anonymous
+    /// poll sent by the bot
+    pub fn on_poll_answer(&self) -> impl Stream<Item = (RcBot, objects::PollAnswer), Error = Error> {
+        let (sender, receiver) = mpsc::unbounded();
+
+        *self.inner.poll_answer_handler.borrow_mut() = Some(sender);
+
+        receiver.map_err(|_| Error::from(ErrorKind::Channel))
+    }
+
+    /// Returns a stream which will yield a shipping query, when a user entered the shipping address
+    /// of a flexible invoice. The query has to be answered with answer_shipping_query.
+    pub fn on_shipping_query(
+        &self,
+    ) -> impl Stream<Item = (RcBot, objects::ShippingQuery), Error = Error> {
+        let (sender, receiver) = mpsc::unbounded();
+
+        *self.inner.shipping_query_handler.borrow_mut() = Some(sender);
+
+        receiver.map_err(|_| Error::from(ErrorKind::Channel))
+    }
+
+    /// Returns a stream which will yield a pre-checkout query, when a user confirmed a payment.
+    /// The query has to be answered within ten seconds, see answer_pre_checkout_in_time.
+    pub fn on_pre_checkout_query(
+        &self,
+    ) -> impl Stream<Item = (RcBot, objects::PreCheckoutQuery), Error = Error> {
+        let (sender, receiver) = mpsc::unbounded();
+
+        *self.inner.pre_checkout_query_handler.borrow_mut() = Some(sender);
+
+        receiver.map_err(|_| Error::from(ErrorKind::Channel))
+    }
+
+    /// Answers a pre-checkout query with the result of `check`, an error is shown to the user. If
+    /// the check fails or doesn't finish before the deadline, the payment is declined in time.
+    /// Returns whether the payment was accepted.
+    pub fn answer_pre_checkout_in_time<F>(
+        &self,
+        query: &objects::PreCheckoutQuery,
+        check: F,
+    ) -> impl Future<Item = (RcBot, bool), Error = Error>
+    where
+        F: Future<Item = Result<(), String>, Error = Error> + 'static,
+    {
+        let bot = self.clone();
+        let query_id = query.id.clone();
+
+        // decline two seconds before the deadline, so the answer still reaches Telegram in time
+        let timeout = Timeout::new(
+            Duration::from_secs(PRE_CHECKOUT_DEADLINE - 2),
+            &self.inner.handle,
+        ).into_future()
+            .flatten()
+            .map(|_| Err(String::from("The payment could not be confirmed in time")))
+            .map_err(|e| Error::from(e.context(ErrorKind::Timer)));
+
+        let check = check.or_else(|e| {
+            error!("The pre-checkout check failed: {}", e);
+
+            Ok::<_, Error>(Err(String::from("The payment could not be confirmed")))
+        });
+
+        check
+            .select(timeout)
+            .map(|(answer, _)| answer)
+            .map_err(|(e, _)| e)
+            .and_then(move |answer| {
+                let ok = answer.is_ok();
+                let request = bot.answer_pre_checkout_query(query_id, ok);
+                let request = match answer {
+                    Ok(()) => request,
+                    Err(msg) => request.error_message(msg),
+                };
+
+                request.send().map(move |(bot, _)| (bot, ok))
+            })
+    }
+
+    /// Returns the update types which are requested from Telegram. These are either set with
+    /// allowed_updates or derived from the registered handlers. If the updates are consumed with
+    /// get_stream, every type may be of interest and None is returned.
+    pub fn allowed_update_types(&self) -> Option<Vec<String>> {
+        if let Some(ref updates) = *self.inner.allowed_updates.borrow() {
+            return Some(updates.clone());
+        }
+
+        if self.inner.raw_updates.get() {
+            return None;
+        }
+
+        let mut updates = Vec::new();
+
+        if !self.inner.handlers.borrow().is_empty() || self.inner.unknown_handler.borrow().is_some() {
+            updates.push("message");
+        }
+        if !self.inner.menus.borrow().is_empty() {
+            updates.push("callback_query");
+        }
+        if self.inner.poll_handler.borrow().is_some() {
+            updates.push("poll");
+        }
+        if self.inner.poll_answer_handler.borrow().is_some() {
+            updates.push("poll_answer");
+        }
+        if self.inner.shipping_query_handler.borrow().is_some() {
+            updates.push("shipping_query");
+        }
+        if self.inner.pre_checkout_query_handler.borrow().is_some() {
+            updates.push("pre_checkout_query");
+        }
+
+        Some(updates.into_iter().map(String::from).collect())
+    }
+
+    /// Prepares a setWebhook call, which only subscribes to the allowed update types
+    pub fn webhook<S: Into<String>>(&self, url: S) -> WrapperSetWebhook {
+        let request = self.set_webhook(url.into());
+
+        match self.allowed_update_types() {
+            Some(updates) => request.allowed_updates(updates),
+            None => request,
+        }
+    }
+
+    /// Registers a menu and returns a stream which will yield the callback query and the data of
+    /// the item, when an item of the menu is selected. The navigation inside of the menu is handled
+    /// by the bot. Fails if the id is invalid or the callback data of a button exceeds 64 bytes.
+    pub fn new_menu(
+        &self,
+        menu: Menu,
+    ) -> Result<impl Stream<Item = (RcBot, objects::CallbackQuery, String), Error = Error>, Error> {
+        menu.validate()?;
+
+        let (sender, receiver) = mpsc::unbounded();
+
+        self.inner
+            .menus
+            .borrow_mut()
+            .insert(menu.id().into(), (menu, sender));
+
+        Ok(receiver.map_err(|_| Error::from(ErrorKind::Channel)))
+    }
+
+    /// Renders the first page of a registered menu, e.g. to show it again with a new message.
+    /// Returns None if no menu with this id is registered.
+    pub fn menu_markup(&self, id: &str) -> Option<objects::InlineKeyboardMarkup> {
+        self.inner.menus.borrow().get(id).map(|&(ref menu, _)| menu.render())
+    }
+
+    /// Downloads a file by its id. The content is returned as a stream of chunks, which fails as
+    /// soon as the download limit is exceeded.
+    pub fn download_file<S: Into<String>>(
+        &self,
+        file_id: S,
+    ) -> impl Future<Item = (RcBot, Box<Stream<Item = Chunk, Error = Error>>), Error = Error> {
+        let limit = self.inner.download_limit.get();
+
+        self.get_file(file_id.into())
+            .send()
+            .and_then(move |(bot, file)| {
+                if file.file_size.map(|size| size as u64 > limit).unwrap_or(false) {
+                    return Err(Error::from(ErrorKind::FileTooLarge));
+                }
+
+                let path = file.file_path.ok_or(ErrorKind::NoFile)?;
+
+                Ok((bot, path))
+            })
+            .and_then(move |(bot, path)| {
+                bot.inner.fetch_file(&path).map(move |body| {
+                    let mut received = 0;
+                    let body = body.map_err(|e| Error::from(e.context(ErrorKind::Hyper)))
+                        .and_then(move |chunk| {
+                            received += chunk.len() as u64;
+
+                            if received > limit {
+                                Err(Error::from(ErrorKind::FileTooLarge))
+                            } else {
+                                Ok(chunk)
+                            }
+                        });
+
+                    (bot, Box::new(body) as Box<Stream<Item = Chunk, Error = Error>>)
+                })
+            })
+    }
+
+    /// Downloads a file by its id into memory
+    pub fn download_file_to_vec<S: Into<String>>(
+        &self,
+        file_id: S,
+    ) -> impl Future<Item = (RcBot, Vec<u8>), Error = Error> {
+        self.download_file(file_id).and_then(|(bot, body)| {
+            body.fold(Vec::new(), |mut content, chunk| {
+                content.extend_from_slice(&chunk);
+
+                Ok::<_, Error>(content)
+            }).map(move |content| (bot, content))
+        })
+    }
+
+    /// Downloads a file by its id and saves it to the path. Returns the number of written bytes.
+    pub fn download_file_to_path<S, P>(
+        &self,
+        file_id: S,
+        path: P,
+    ) -> impl Future<Item = (RcBot, u64), Error = Error>
+    where
+        S: Into<String>,
+        P: AsRef<Path> + 'static,
+    {
+        let bot = self.clone();
+
+        fs::File::create(path)
+            .context(ErrorKind::IO)
+            .map_err(Error::from)
+            .into_future()
+            .and_then(move |file| bot.download_file_to_writer(file_id, file))
+            .map(|(bot, _, written)| (bot, written))
+    }
+
+    /// Downloads a file by its id and streams the content into the writer. Returns the writer and
+    /// the number of written bytes.
+    pub fn download_file_to_writer<S, W>(
+        &self,
+        file_id: S,
+        writer: W,
+    ) -> impl Future<Item = (RcBot, W, u64), Error = Error>
+    where
+        S: Into<String>,
+        W: Write + 'static,
+    {
+        self.download_file(file_id).and_then(move |(bot, body)| {
+            body.fold((writer, 0), |(mut writer, written), chunk| {
+                writer
+                    .write_all(&chunk)
+                    .context(ErrorKind::IO)
+                    .map_err(Error::from)
+                    .map(|_| (writer, written + chunk.len() as u64))
+            }).map(move |(writer, written)| (bot, writer, written))
+        })
+    }
+
     /// Register a new commnd
     pub fn register<T>(&self, hnd: T)
     where
@@ -275,6 +740,17 @@ impl RcBot {
     /// The message is forwarded to the returned stream if no command was found
     pub fn get_stream<'a>(
         &'a self,
+    ) -> impl Stream<Item = (RcBot, objects::Update), Error = Error> + 'a {
+        // the caller consumes the remaining updates, so every type may be of interest
+        self.inner.raw_updates.set(true);
+
+        self.update_stream()
+    }
+
+    /// Polls the updates and forwards them to the registered handlers, the remaining updates are
+    /// returned
+    fn update_stream<'a>(
+        &'a self,
     ) -> impl Stream<Item = (RcBot, objects::Update), Error = Error> + 'a {
         use functions::*;
 
@@ -285,10 +761,14 @@ impl RcBot {
             .flatten()
             .map_err(|x| Error::from(x.context(ErrorKind::IntervalTimer)))
             .and_then(move |_| {
-                self.get_updates()
+                let request = self.get_updates()
                     .offset(self.inner.last_id.get())
-                    .timeout(self.inner.timeout.get() as i64)
-                    .send()
+                    .timeout(self.inner.timeout.get() as i64);
+
+                match self.allowed_update_types() {
+                    Some(updates) => request.allowed_updates(updates),
+                    None => request,
+                }.send()
             })
             .map(|(_, x)| {
                 stream::iter_result(
@@ -309,6 +789,23 @@ impl RcBot {
             .filter_map(move |mut val| {
                 debug!("Got an update from Telegram: {:?}", val);
 
+                if let Some(query) = val.callback_query.take() {
+                    match menu::route(self, query) {
+                        Some(query) => val.callback_query = Some(query),
+                        None => return None,
+                    }
+                }
+
+                if self.dispatch(&self.inner.poll_handler, &mut val.poll)
+                    || self.dispatch(&self.inner.poll_answer_handler, &mut val.poll_answer)
+                    || self.dispatch(&self.inner.shipping_query_handler, &mut val.shipping_query)
+                    || self.dispatch(
+                        &self.inner.pre_checkout_query_handler,
+                        &mut val.pre_checkout_query,
+                    ) {
+                    return None;
+                }
+
                 let mut sndr: Option<UnboundedSender<(RcBot, objects::Message)>> = None;
 
                 if let Some(ref mut message) = val.message {
@@ -346,6 +843,30 @@ impl RcBot {
             })
     }
 
+    /// Forwards a part of an update to its handler. Returns false and keeps the value in the update,
+    /// if no handler is registered.
+    fn dispatch<T>(
+        &self,
+        handler: &RefCell<Option<UnboundedSender<(RcBot, T)>>>,
+        value: &mut Option<T>,
+    ) -> bool {
+        match (handler.borrow().as_ref(), value.take()) {
+            (Some(sender), Some(inner)) => {
+                sender
+                    .unbounded_send((self.clone(), inner))
+                    .unwrap_or_else(|e| error!("Error: {}", e));
+
+                true
+            }
+            (None, inner) => {
+                *value = inner;
+
+                false
+            }
+            (_, None) => false,
+        }
+    }
+
     /// helper function to start the event loop
     pub fn run<'a>(&'a self, core: &mut Core) -> Result<(), Error> {
         // create a local copy of the bot to circumvent lifetime issues
@@ -359,7 +880,27 @@ impl RcBot {
             });
         // spawn the task
         self.inner.handle.spawn(resolve_name.map_err(|_| ()));
-        core.run(self.get_stream().for_each(|_| Ok(())).into_future())
+        // publish the registered commands if requested
+        if self.inner.publish_commands.get() {
+            let publish = self.set_my_commands(self.bot_commands())
+                .send()
+                .map(|_| ())
+                .map_err(|e| error!("Could not publish the commands: {}", e));
+
+            self.inner.handle.spawn(publish);
+        }
+        // a registered webhook blocks getUpdates, therefore it's removed before polling starts
+        let delete_webhook = self.delete_webhook()
+            .send()
+            .map(|_| ())
+            .or_else(|e| {
+                error!("Could not delete the webhook: {}", e);
+
+                Ok::<_, Error>(())
+            });
+        let polling = delete_webhook.and_then(move |_| self.update_stream().for_each(|_| Ok(())));
+
+        core.run(polling)
             .context(ErrorKind::Tokio)
             .map_err(Error::from)
     }
@@ -0,0 +1,136 @@
+//! Typed callback data for inline keyboards
+//!
+//! The callback data of a button is limited to 64 bytes. Types which implement `CallbackData`
+//! are packed into a compact string: a short tag which identifies the type, followed by the
+//! values of the fields in JSON, e.g. `page|42,"name"`. The trait can be derived with
+//! telebot-derive, the tag defaults to the name of the struct and can be changed with
+//! `#[callback = "tag"]`. The tag may not contain `|`.
+//!
+//! ```ignore
+//! #[derive(CallbackData)]
+//! #[callback = "p"]
+//! struct Page {
+//!     id: i64,
+//!     page: u32,
+//! }
+//!
+//! let keyboard = InlineKeyboard::new()
+//!     .button_data("Next", &Page { id: 1, page: 2 })?
+//!     .build();
+//!
+//! // and in the callback handler
+//! let page: Page = query.decode()?;
+//! ```
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json;
+use failure::ResultExt;
+// used by the derived implementation
+pub use failure::Error;
+use error::ErrorKind;
+
+/// The maximum size of callback data in bytes
+pub const CALLBACK_DATA_LIMIT: usize = 64;
+
+/// A type which can be encoded into the callback data of a button
+pub trait CallbackData: Sized {
+    /// Encodes the value, fails if the result exceeds 64 bytes
+    fn encode(&self) -> Result<String, Error>;
+
+    /// Decodes the value from the callback data of a query
+    fn decode(data: &str) -> Result<Self, Error>;
+}
+
+/// Returns the tag of encoded callback data
+pub fn tag_of(data: &str) -> Option<&str> {
+    data.find('|').map(|pos| &data[..pos])
+}
+
+/// Encodes the fields of a type, which are passed as a tuple. Used by the derived implementation.
+pub fn encode_fields<T: Serialize>(tag: &str, fields: &T) -> Result<String, Error> {
+    // the data couldn't be decoded, because the tag ends with the first '|'
+    if tag.contains('|') {
+        return Err(Error::from(ErrorKind::CallbackTag));
+    }
+
+    let json = serde_json::to_string(fields).context(ErrorKind::JsonSerialize)?;
+
+    // the brackets of the tuple are implied
+    let json = if json.starts_with('[') && json.ends_with(']') {
+        &json[1..json.len() - 1]
+    } else {
+        &json
+    };
+
+    let data = format!("{}|{}", tag, json);
+    if data.len() > CALLBACK_DATA_LIMIT {
+        return Err(Error::from(ErrorKind::CallbackDataTooLong));
+    }
+
+    Ok(data)
+}
+
+/// Decodes the fields of a type as a tuple. Used by the derived implementation.
+pub fn decode_fields<T: DeserializeOwned>(tag: &str, data: &str) -> Result<T, Error> {
+    match tag_of(data) {
+        Some(found) if found == tag => {}
+        _ => return Err(Error::from(ErrorKind::CallbackData)),
+    }
+
+    let json = format!("[{}]", &data[tag.len() + 1..]);
+
+    Ok(serde_json::from_str(&json).context(ErrorKind::CallbackData)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unit_struct_round_trip() {
+        let data = encode_fields("unit", &[0u8; 0]).unwrap();
+
+        assert_eq!(data, "unit|");
+        assert_eq!(tag_of(&data), Some("unit"));
+        let _: [u8; 0] = decode_fields("unit", &data).unwrap();
+    }
+
+    #[test]
+    fn single_field_round_trip() {
+        let data = encode_fields("page", &(&42u32,)).unwrap();
+
+        assert_eq!(data, "page|42");
+        let (page,): (u32,) = decode_fields("page", &data).unwrap();
+        assert_eq!(page, 42);
+    }
+
+    #[test]
+    fn string_with_separators_round_trip() {
+        let name = "a,\"b\"|c".to_string();
+        let data = encode_fields("s", &(&1i64, &name)).unwrap();
+
+        assert_eq!(data, "s|1,\"a,\\\"b\\\"|c\"");
+        assert_eq!(tag_of(&data), Some("s"));
+        let (id, decoded): (i64, String) = decode_fields("s", &data).unwrap();
+        assert_eq!((id, decoded), (1, name));
+    }
+
+    #[test]
+    fn data_limit() {
+        // the tag, the separator and the quotes leave 60 bytes for the string
+        let fits = "x".repeat(60);
+        let data = encode_fields("t", &(&fits,)).unwrap();
+
+        assert_eq!(data.len(), CALLBACK_DATA_LIMIT);
+        assert!(encode_fields("t", &(&format!("{}x", fits),)).is_err());
+    }
+
+    #[test]
+    fn wrong_tag_is_rejected() {
+        let data = encode_fields("a", &(&1u8,)).unwrap();
+
+        assert!(decode_fields::<(u8,)>("b", &data).is_err());
+        assert!(encode_fields("a|b", &(&1u8,)).is_err());
+    }
+}
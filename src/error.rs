@@ -98,6 +98,38 @@ pub enum ErrorKind {
     #[fail(display = "Expected JSON to be a Map, got something else")]
     JsonNotMap,
 
+    // indicates that the bot name wasn't resolved by getMe yet
+    #[fail(display = "The name of the bot is unknown")]
+    NoBotName,
+
+    #[fail(display = "The start payload is longer than 64 characters")]
+    PayloadTooLong,
+
+    #[fail(display = "The file exceeds the download limit")]
+    FileTooLarge,
+
+    // indicates that the file server responded with an error status
+    #[fail(display = "Failed to download the file")]
+    Download,
+
+    #[fail(display = "The callback data is longer than 64 bytes")]
+    CallbackDataTooLong,
+
+    // indicates that the id of a menu is empty or contains ':' or '/'
+    #[fail(display = "The id of a menu may not be empty or contain ':' or '/'")]
+    MenuId,
+
+    // indicates that the callback data is missing or belongs to another type
+    #[fail(display = "Failed to decode the callback data")]
+    CallbackData,
+
+    #[fail(display = "The tag of callback data may not contain '|'")]
+    CallbackTag,
+
+    // indicates that a timer couldn't be created
+    #[fail(display = "Failed to create a timer")]
+    Timer,
+
     // indicates an unknown error
     #[fail(display = "Unknown error")]
     Unknown,
@@ -1,12 +1,16 @@
 //! A Telegram file which contains a readable source and a filename
 //!
-//! The filename should be such that it represents the content type.
+//! The filename should be such that it represents the content type. If the content is already in
+//! memory or comes from an asynchronous source (e.g. the body of another download), the `bytes` and
+//! `stream` constructors can be used. They allow to specify the MIME type and the length explicitly.
 
 use std::io::Read;
 use std::convert::TryFrom;
 use std::path::Path;
 use failure::Error;
 use error::ErrorKind;
+use futures::Stream;
+use hyper::Chunk;
 
 /// A Telegram file which contains a readable source and a filename
 pub enum File {
@@ -17,6 +21,70 @@ pub enum File {
     Disk {
         path: String,
     },
+    Bytes {
+        name: String,
+        content: Vec<u8>,
+        mime: Option<String>,
+    },
+    Stream {
+        name: String,
+        source: Box<Stream<Item = Chunk, Error = Error>>,
+        mime: Option<String>,
+        length: Option<u64>,
+    },
+}
+
+impl File {
+    /// Construct a Telegram file from a buffer
+    pub fn bytes<S, B>(name: S, content: B) -> File
+    where
+        S: Into<String>,
+        B: Into<Vec<u8>>,
+    {
+        File::Bytes {
+            name: name.into(),
+            content: content.into(),
+            mime: None,
+        }
+    }
+
+    /// Construct a Telegram file from an asynchronous stream. The content is only polled while
+    /// the request is sent.
+    pub fn stream<S, T>(name: S, source: T) -> File
+    where
+        S: Into<String>,
+        T: Stream<Item = Chunk, Error = Error> + 'static,
+    {
+        File::Stream {
+            name: name.into(),
+            source: Box::new(source),
+            mime: None,
+            length: None,
+        }
+    }
+
+    /// Sets the MIME type of the content. By default the type is guessed from the extension of the
+    /// filename, unknown extensions are sent as application/octet-stream.
+    pub fn mime<S: Into<String>>(mut self, val: S) -> File {
+        match self {
+            File::Bytes { ref mut mime, .. } | File::Stream { ref mut mime, .. } => {
+                *mime = Some(val.into());
+            }
+            _ => warn!("The MIME type can only be set for bytes and streams"),
+        }
+
+        self
+    }
+
+    /// Sets the length of a stream in bytes, which allows to send the request with a content length
+    pub fn length(mut self, val: u64) -> File {
+        match self {
+            File::Stream { ref mut length, .. } => *length = Some(val),
+            _ => warn!("The length can only be set for streams"),
+        }
+
+        self
+    }
 }
 
 /// Construct a Telegram file from a local path
@@ -5,35 +5,87 @@
 use bot::{Bot, RcBot};
 use serde_json;
 use objects;
-use objects::Integer;
+use objects::{ChatId, Integer};
 use file;
 use failure::{Error, Fail};
 use error::ErrorKind;
-use futures::Future;
+use futures::{stream, Future, Stream};
 use std::rc::Rc;
 use std::convert::{From, TryInto};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use erased_serde::Serialize;
+use text;
 
 /// The strongly typed version of the parse_mode field which indicates the type of text
+#[derive(Debug, Clone, PartialEq)]
 pub enum ParseMode {
     Markdown,
+    MarkdownV2,
     HTML,
     Text,
+    Custom(String),
 }
 
-impl Into<String> for ParseMode {
-    fn into(self) -> String {
-        let tmp = match self {
+impl ParseMode {
+    /// Returns the name used by the Telegram API
+    pub fn as_str(&self) -> &str {
+        match *self {
             ParseMode::Markdown => "Markdown",
+            ParseMode::MarkdownV2 => "MarkdownV2",
             ParseMode::HTML => "HTML",
             ParseMode::Text => "Text",
-        };
+            ParseMode::Custom(ref name) => name,
+        }
+    }
+}
+
+impl Into<String> for ParseMode {
+    fn into(self) -> String {
+        self.as_str().into()
+    }
+}
 
-        tmp.into()
+/// Parses the name of a parse mode, unknown names are passed to Telegram unchanged
+impl<'a> From<&'a str> for ParseMode {
+    fn from(name: &'a str) -> Self {
+        match name.to_lowercase().as_str() {
+            "markdown" => ParseMode::Markdown,
+            "markdownv2" => ParseMode::MarkdownV2,
+            "html" => ParseMode::HTML,
+            "text" => ParseMode::Text,
+            _ => ParseMode::Custom(name.into()),
+        }
+    }
+}
+
+impl From<String> for ParseMode {
+    fn from(name: String) -> Self {
+        ParseMode::from(name.as_str())
+    }
+}
+
+impl ::serde::Serialize for ParseMode {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> ::serde::Deserialize<'de> for ParseMode {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        let name = String::deserialize(deserializer)?;
+
+        Ok(ParseMode::from(name))
     }
 }
 
 /// The strongly typed version of the action field which indicates the type of action
+#[derive(Debug, Clone, PartialEq)]
 pub enum Action {
     Typing,
     UploadPhoto,
@@ -41,8 +93,115 @@ pub enum Action {
     UploadVideo,
     RecordAudio,
     UploadAudio,
+    RecordVoice,
+    UploadVoice,
     UploadDocument,
+    ChooseSticker,
     FindLocation,
+    RecordVideoNote,
+    UploadVideoNote,
+    Custom(String),
+}
+
+impl Action {
+    /// Returns the name used by the Telegram API
+    pub fn as_str(&self) -> &str {
+        match *self {
+            Action::Typing => "typing",
+            Action::UploadPhoto => "upload_photo",
+            Action::RecordVideo => "record_video",
+            Action::UploadVideo => "upload_video",
+            Action::RecordAudio => "record_audio",
+            Action::UploadAudio => "upload_audio",
+            Action::RecordVoice => "record_voice",
+            Action::UploadVoice => "upload_voice",
+            Action::UploadDocument => "upload_document",
+            Action::ChooseSticker => "choose_sticker",
+            Action::FindLocation => "find_location",
+            Action::RecordVideoNote => "record_video_note",
+            Action::UploadVideoNote => "upload_video_note",
+            Action::Custom(ref name) => name,
+        }
+    }
+}
+
+impl Into<String> for Action {
+    fn into(self) -> String {
+        self.as_str().into()
+    }
+}
+
+/// Parses the name of an action, unknown names are passed to Telegram unchanged
+impl<'a> From<&'a str> for Action {
+    fn from(name: &'a str) -> Self {
+        match name {
+            "typing" => Action::Typing,
+            "upload_photo" => Action::UploadPhoto,
+            "record_video" => Action::RecordVideo,
+            "upload_video" => Action::UploadVideo,
+            "record_audio" => Action::RecordAudio,
+            "upload_audio" => Action::UploadAudio,
+            "record_voice" => Action::RecordVoice,
+            "upload_voice" => Action::UploadVoice,
+            "upload_document" => Action::UploadDocument,
+            "choose_sticker" => Action::ChooseSticker,
+            "find_location" => Action::FindLocation,
+            "record_video_note" => Action::RecordVideoNote,
+            "upload_video_note" => Action::UploadVideoNote,
+            name => Action::Custom(name.into()),
+        }
+    }
+}
+
+impl From<String> for Action {
+    fn from(name: String) -> Self {
+        Action::from(name.as_str())
+    }
+}
+
+impl ::serde::Serialize for Action {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// A point in time, which is sent as a unix timestamp. It can be created from a timestamp, a
+/// SystemTime or a Duration, which is relative to the current time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnixTime(pub Integer);
+
+impl From<Integer> for UnixTime {
+    fn from(timestamp: Integer) -> Self {
+        UnixTime(timestamp)
+    }
+}
+
+impl From<SystemTime> for UnixTime {
+    fn from(time: SystemTime) -> Self {
+        let seconds = time.duration_since(UNIX_EPOCH)
+            .map(|x| x.as_secs())
+            .unwrap_or(0);
+
+        UnixTime(seconds as Integer)
+    }
+}
+
+impl From<Duration> for UnixTime {
+    fn from(duration: Duration) -> Self {
+        UnixTime::from(SystemTime::now() + duration)
+    }
+}
+
+impl ::serde::Serialize for UnixTime {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        serializer.serialize_i64(self.0)
+    }
 }
 
 /// Possible types of reply markups
@@ -93,23 +252,6 @@ impl From<objects::ForceReply> for ReplyMarkup {
     }
 }
 
-impl Into<String> for Action {
-    fn into(self) -> String {
-        let tmp = match self {
-            Action::Typing => "Typing",
-            Action::UploadPhoto => "UploadPhoto",
-            Action::RecordVideo => "RecordVideo",
-            Action::UploadVideo => "UploadVideo",
-            Action::RecordAudio => "RecordVideo",
-            Action::UploadAudio => "UploadAudio",
-            Action::UploadDocument => "UploadDocument",
-            Action::FindLocation => "FindLocation",
-        };
-
-        tmp.into()
-    }
-}
-
 /// A simple method for testing your bot's auth token. Requires no parameters. Returns basic
 /// information about the bot in form of a User object.
 #[derive(TelegramFunction, Serialize)]
@@ -135,16 +277,65 @@ pub struct GetUpdates {
     allowed_updates: Option<Vec<String>>,
 }
 
+/// Use this method to specify a url and receive incoming updates via an outgoing webhook. A self
+/// signed certificate can be uploaded with the `certificate_file` method. Returns True on success.
+#[derive(TelegramFunction, Serialize)]
+#[call = "setWebhook"]
+#[answer = "Boolean"]
+#[function = "set_webhook"]
+pub struct SetWebhook {
+    url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ip_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_connections: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    allowed_updates: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    drop_pending_updates: Option<bool>,
+}
+
+impl WrapperSetWebhook {
+    /// Uploads the public key certificate, the certificate can't be given as an url or file id
+    pub fn certificate_file<S>(self, val: S) -> Self
+    where
+        S: TryInto<file::File>,
+    {
+        self.attach("certificate", val)
+    }
+}
+
+/// Use this method to remove webhook integration if you decide to switch back to getUpdates.
+/// Returns True on success.
+#[derive(TelegramFunction, Serialize)]
+#[call = "deleteWebhook"]
+#[answer = "Boolean"]
+#[function = "delete_webhook"]
+pub struct DeleteWebhook {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    drop_pending_updates: Option<bool>,
+}
+
+/// Use this method to get current webhook status. If the bot is using getUpdates, will return an
+/// object with the url field empty.
+#[derive(TelegramFunction, Serialize)]
+#[call = "getWebhookInfo"]
+#[answer = "WebhookInfo"]
+#[function = "get_webhook_info"]
+pub struct GetWebhookInfo;
+
 /// Use this method to send text messages. On success, the sent Message is returned.
 #[derive(TelegramFunction, Serialize)]
 #[call = "sendMessage"]
 #[answer = "Message"]
 #[function = "message"]
 pub struct Message {
-    chat_id: Integer,
+    chat_id: ChatId,
     text: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    parse_mode: Option<String>,
+    parse_mode: Option<ParseMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entities: Option<Vec<objects::MessageEntity>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     disable_web_page_preview: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -155,6 +346,115 @@ pub struct Message {
     reply_markup: Option<ReplyMarkup>,
 }
 
+impl WrapperMessage {
+    /// Sends the text in multiple messages, if it exceeds the limit of 4096 characters. The text is
+    /// split between paragraphs, lines or words without breaking the formatting. The first message
+    /// replies to `reply_to_message_id` and the last one carries the reply markup. The sent
+    /// messages are returned in order.
+    pub fn send_chunked<'a>(
+        self,
+    ) -> impl Future<Item = (RcBot, Vec<objects::Message>), Error = Error> + 'a {
+        let Message {
+            chat_id,
+            text: content,
+            parse_mode,
+            entities,
+            disable_web_page_preview,
+            disable_notificaton,
+            reply_to_message_id,
+            mut reply_markup,
+        } = self.inner;
+
+        let mut chunks: Vec<(String, Option<Vec<objects::MessageEntity>>)> = match entities {
+            Some(ref entities) => text::split_entities(&content, entities, text::MESSAGE_LIMIT)
+                .into_iter()
+                .map(|(chunk, entities)| (chunk, Some(entities)))
+                .collect(),
+            None => text::split(
+                &content,
+                parse_mode.clone().unwrap_or(ParseMode::Text),
+                text::MESSAGE_LIMIT,
+            ).into_iter()
+                .map(|chunk| (chunk, None))
+                .collect(),
+        };
+
+        // let Telegram decide what to do with an empty text
+        if chunks.is_empty() {
+            chunks.push((content.clone(), entities.clone()));
+        }
+
+        let last = chunks.len() - 1;
+        let messages = chunks
+            .into_iter()
+            .enumerate()
+            .map(|(i, (chunk, entities))| Message {
+                chat_id: chat_id.clone(),
+                text: chunk,
+                parse_mode: parse_mode.clone(),
+                entities: entities,
+                disable_web_page_preview: disable_web_page_preview,
+                disable_notificaton: disable_notificaton,
+                reply_to_message_id: if i == 0 { reply_to_message_id } else { None },
+                reply_markup: if i == last { reply_markup.take() } else { None },
+            })
+            .collect::<Vec<_>>();
+
+        let bot = self.bot;
+        let bot2 = bot.clone();
+
+        stream::iter_ok(messages)
+            .and_then(move |inner| {
+                WrapperMessage {
+                    bot: bot.clone(),
+                    inner: inner,
+                    files: Vec::new(),
+                }.send()
+                    .map(|(_, msg)| msg)
+            })
+            .collect()
+            .map(move |msgs| (RcBot { inner: bot2 }, msgs))
+    }
+}
+
+/// Use this method to forward messages of any kind. On success, the sent Message is returned.
+#[derive(TelegramFunction, Serialize)]
+#[call = "forwardMessage"]
+#[answer = "Message"]
+#[function = "forward_message"]
+pub struct ForwardMessage {
+    chat_id: ChatId,
+    from_chat_id: ChatId,
+    message_id: Integer,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disable_notification: Option<bool>,
+}
+
+/// Use this method to copy messages of any kind. The method is analogous to forwardMessage, but
+/// the copied message doesn't have a link to the original message. The caption and the reply
+/// markup can be replaced. Returns the MessageId of the sent message on success.
+#[derive(TelegramFunction, Serialize)]
+#[call = "copyMessage"]
+#[answer = "MessageId"]
+#[function = "copy_message"]
+pub struct CopyMessage {
+    chat_id: ChatId,
+    from_chat_id: ChatId,
+    message_id: Integer,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    caption: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parse_mode: Option<ParseMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    caption_entities: Option<Vec<objects::MessageEntity>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disable_notification: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_to_message_id: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_markup: Option<ReplyMarkup>,
+}
+
 /// Use this method to send photos. On success, the sent Message is returned.
 #[derive(TelegramFunction, Serialize)]
 #[call = "sendPhoto"]
@@ -162,12 +462,14 @@ pub struct Message {
 #[function = "photo"]
 #[file_kind = "photo"]
 pub struct SendPhoto {
-    chat_id: Integer,
+    chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     photo: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     caption: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    parse_mode: Option<ParseMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     disable_notification: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     reply_to_message_id: Option<bool>,
@@ -185,13 +487,18 @@ pub struct SendPhoto {
 #[answer = "Message"]
 #[function = "audio"]
 #[file_kind = "audio"]
+#[attach = "thumb"]
 pub struct SendAudio {
-    chat_id: Integer,
+    chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     audio: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    thumb: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     caption: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    parse_mode: Option<ParseMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     duration: Option<Integer>,
     #[serde(skip_serializing_if = "Option::is_none")]
     performer: Option<String>,
@@ -213,13 +520,18 @@ pub struct SendAudio {
 #[answer = "Message"]
 #[function = "document"]
 #[file_kind = "document"]
+#[attach = "thumb"]
 pub struct SendDocument {
-    chat_id: Integer,
+    chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     document: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    thumb: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     caption: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    parse_mode: Option<ParseMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     disable_notification: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     reply_to_message_id: Option<Integer>,
@@ -234,7 +546,7 @@ pub struct SendDocument {
 #[function = "sticker"]
 #[file_kind = "sticker"]
 pub struct SendSticker {
-    chat_id: Integer,
+    chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     sticker: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -253,11 +565,14 @@ pub struct SendSticker {
 #[answer = "Message"]
 #[function = "video"]
 #[file_kind = "video"]
+#[attach = "thumb"]
 pub struct SendVideo {
-    chat_id: Integer,
+    chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     video: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    thumb: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     duration: Option<Integer>,
     #[serde(skip_serializing_if = "Option::is_none")]
     width: Option<Integer>,
@@ -266,6 +581,8 @@ pub struct SendVideo {
     #[serde(skip_serializing_if = "Option::is_none")]
     caption: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    parse_mode: Option<ParseMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     disable_notification: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     reply_to_message_id: Option<Integer>,
@@ -284,14 +601,130 @@ pub struct SendVideo {
 #[function = "voice"]
 #[file_kind = "voice"]
 pub struct SendVoice {
-    chat_id: Integer,
+    chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     voice: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     caption: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    parse_mode: Option<ParseMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disable_notification: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_to_message_id: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_markup: Option<ReplyMarkup>,
+}
+
+/// Use this method to send animation files (GIF or H.264/MPEG-4 AVC video without sound). On
+/// success, the sent Message is returned. Bots can currently send animation files of up to 50 MB
+/// in size, this limit may be changed in the future.
+#[derive(TelegramFunction, Serialize)]
+#[call = "sendAnimation"]
+#[answer = "Message"]
+#[function = "animation"]
+#[file_kind = "animation"]
+#[attach = "thumb"]
+pub struct SendAnimation {
+    chat_id: ChatId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    animation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thumb: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    width: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    height: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    caption: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parse_mode: Option<ParseMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disable_notification: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_to_message_id: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_markup: Option<ReplyMarkup>,
+}
+
+/// As of v.4.0, Telegram clients support rounded square mp4 videos of up to 1 minute long. Use
+/// this method to send video messages. On success, the sent Message is returned.
+#[derive(TelegramFunction, Serialize)]
+#[call = "sendVideoNote"]
+#[answer = "Message"]
+#[function = "video_note"]
+#[file_kind = "video_note"]
+#[attach = "thumb"]
+pub struct SendVideoNote {
+    chat_id: ChatId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    video_note: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thumb: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     duration: Option<Integer>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    length: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disable_notification: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_to_message_id: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_markup: Option<ReplyMarkup>,
+}
+
+/// Use this method to send an animated emoji that will display a random value. The emoji defaults
+/// to a dice. On success, the sent Message is returned.
+#[derive(TelegramFunction, Serialize)]
+#[call = "sendDice"]
+#[answer = "Message"]
+#[function = "dice"]
+pub struct SendDice {
+    chat_id: ChatId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    emoji: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disable_notification: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_to_message_id: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_markup: Option<ReplyMarkup>,
+}
+
+/// Use this method to send a native poll. A quiz can be sent by setting the kind to quiz and
+/// specifying the correct option. On success, the sent Message is returned.
+#[derive(TelegramFunction, Serialize)]
+#[call = "sendPoll"]
+#[answer = "Message"]
+#[function = "poll"]
+pub struct SendPoll {
+    chat_id: ChatId,
+    question: String,
+    options: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_anonymous: Option<bool>,
+    #[serde(rename = "type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kind: Option<objects::PollKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    allows_multiple_answers: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    correct_option_id: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    explanation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    explanation_parse_mode: Option<ParseMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    open_period: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    close_date: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_closed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     disable_notification: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     reply_to_message_id: Option<Integer>,
@@ -299,16 +732,106 @@ pub struct SendVoice {
     reply_markup: Option<ReplyMarkup>,
 }
 
+impl WrapperSendPoll {
+    /// Turns the poll into a quiz, the option with the index `correct_option_id` is the right
+    /// answer
+    pub fn quiz(self, correct_option_id: Integer) -> Self {
+        self.kind(objects::PollKind::Quiz)
+            .correct_option_id(correct_option_id)
+    }
+}
+
+/// Use this method to stop a poll which was sent by the bot. On success, the stopped Poll with the
+/// final results is returned.
+#[derive(TelegramFunction, Serialize)]
+#[call = "stopPoll"]
+#[answer = "Poll"]
+#[function = "stop_poll"]
+pub struct StopPoll {
+    chat_id: ChatId,
+    message_id: Integer,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_markup: Option<objects::InlineKeyboardMarkup>,
+}
+
+/// Implements a setter for captions, which cuts the caption to the limit of 1024 characters
+macro_rules! caption_trimmed {
+    ($($wrapper:ident),*) => {
+        $(
+            impl $wrapper {
+                /// Sets the caption and cuts it at a word boundary, if it exceeds the limit of
+                /// 1024 characters. The parse mode has to be set before, so no entity is broken.
+                pub fn caption_trimmed<S: AsRef<str>>(self, caption: S) -> Self {
+                    let mode = self.inner.parse_mode.clone().unwrap_or(ParseMode::Text);
+
+                    self.caption(text::trim(caption.as_ref(), mode, text::CAPTION_LIMIT))
+                }
+            }
+        )*
+    }
+}
+
+caption_trimmed!(
+    WrapperSendPhoto,
+    WrapperSendAudio,
+    WrapperSendDocument,
+    WrapperSendVideo,
+    WrapperSendVoice,
+    WrapperSendAnimation
+);
+
+/// Use this method to send a group of photos, videos, documents or audios as an album. Documents
+/// and audio files can be only grouped in an album with messages of the same type. On success, an
+/// array of the sent Messages is returned.
+#[derive(TelegramFunction, Serialize)]
+#[call = "sendMediaGroup"]
+#[answer = "Vector<objects::Message>"]
+#[function = "media_group"]
+pub struct SendMediaGroup {
+    chat_id: ChatId,
+    media: Vec<objects::InputMedia>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disable_notification: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_to_message_id: Option<Integer>,
+}
+
+impl WrapperSendMediaGroup {
+    /// Appends a photo or video, which is referenced by a file_id or an url
+    pub fn media<M: Into<objects::InputMedia>>(mut self, media: M) -> Self {
+        self.inner.media.push(media.into());
+
+        self
+    }
+
+    /// Appends a photo or video and uploads the file as its content. The media field of the
+    /// object is replaced with a reference to the upload.
+    pub fn upload<M, S>(mut self, media: M, val: S) -> Self
+    where
+        M: Into<objects::InputMedia>,
+        S: TryInto<file::File>,
+    {
+        let name = format!("media{}", self.inner.media.len());
+        let mut media = media.into();
+        media.set_media(format!("attach://{}", name));
+        self.inner.media.push(media);
+
+        self.attach(&name, val)
+    }
+}
+
 /// Use this method to send point on the map. On success, the sent Message is returned.
 #[derive(TelegramFunction, Serialize)]
 #[call = "sendLocation"]
 #[answer = "Message"]
 #[function = "location"]
 pub struct SendLocation {
-    chat_id: Integer,
+    chat_id: ChatId,
     latitude: f32,
     longitude: f32,
     #[serde(skip_serializing_if = "Option::is_none")]
+    live_period: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     disable_notification: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     reply_to_message_id: Option<Integer>,
@@ -322,7 +845,7 @@ pub struct SendLocation {
 #[answer = "Message"]
 #[function = "venue"]
 pub struct SendVenue {
-    chat_id: Integer,
+    chat_id: ChatId,
     latitude: f32,
     longitude: f32,
     title: String,
@@ -343,7 +866,7 @@ pub struct SendVenue {
 #[answer = "Message"]
 #[function = "contact"]
 pub struct SendContact {
-    chat_id: Integer,
+    chat_id: ChatId,
     phone_number: String,
     first_name: String,
     last_name: Option<String>,
@@ -363,8 +886,8 @@ pub struct SendContact {
 #[answer = "Boolean"]
 #[function = "chat_action"]
 pub struct SendAction {
-    chat_id: Integer,
-    action: String,
+    chat_id: ChatId,
+    action: Action,
 }
 
 /// Use this method to send a game. On success, the sent Message is returned.
@@ -450,8 +973,26 @@ pub struct GetFile {
 #[answer = "Boolean"]
 #[function = "kick_chat_member"]
 pub struct KickChatMember {
-    chat_id: Integer,
+    chat_id: ChatId,
+    user_id: Integer,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    until_date: Option<UnixTime>,
+}
+
+/// Use this method to ban a user in a group, a supergroup or a channel. The user is banned until
+/// the given date, which can be a Duration relative to now. Users banned for more than 366 days or
+/// less than 30 seconds are banned forever. Returns True on success.
+#[derive(TelegramFunction, Serialize)]
+#[call = "banChatMember"]
+#[answer = "Boolean"]
+#[function = "ban_chat_member"]
+pub struct BanChatMember {
+    chat_id: ChatId,
     user_id: Integer,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    until_date: Option<UnixTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    revoke_messages: Option<bool>,
 }
 
 /// Use this method for your bot to leave a group, supergroup or channel. Returns True on
@@ -461,7 +1002,7 @@ pub struct KickChatMember {
 #[answer = "Boolean"]
 #[function = "leave_chat"]
 pub struct LeaveChat {
-    chat_id: Integer,
+    chat_id: ChatId,
 }
 
 /// Use this method to unban a previously kicked user in a supergroup. The user will not return to
@@ -472,7 +1013,7 @@ pub struct LeaveChat {
 #[answer = "Boolean"]
 #[function = "unban_chat_member"]
 pub struct UnbanChatMember {
-    chat_id: Integer,
+    chat_id: ChatId,
     user_id: Integer,
 }
 
@@ -484,10 +1025,12 @@ pub struct UnbanChatMember {
 #[answer = "Boolean"]
 #[function = "restrict_chat_member"]
 pub struct RestrictChatMember {
-    chat_id: Integer,
+    chat_id: ChatId,
     user_id: Integer,
     #[serde(skip_serializing_if = "Option::is_none")]
-    until_date: Option<Integer>,
+    until_date: Option<UnixTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    permissions: Option<objects::ChatPermissions>,
     #[serde(skip_serializing_if = "Option::is_none")]
     can_send_messages: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -506,7 +1049,7 @@ pub struct RestrictChatMember {
 #[answer = "Boolean"]
 #[function = "promote_chat_member"]
 pub struct PromoteChatMember {
-    chat_id: Integer,
+    chat_id: ChatId,
     user_id: Integer,
     #[serde(skip_serializing_if = "Option::is_none")]
     can_change_into: Option<bool>,
@@ -534,7 +1077,98 @@ pub struct PromoteChatMember {
 #[answer = "Link"]
 #[function = "export_chat_invite_link"]
 pub struct ExportChatInviteLink {
-    chat_id: Integer,
+    chat_id: ChatId,
+}
+
+/// Use this method to create an additional invite link for a chat. The bot must be an
+/// administrator in the chat for this to work and must have the appropriate admin rights. Returns
+/// the new invite link as ChatInviteLink object.
+#[derive(TelegramFunction, Serialize)]
+#[call = "createChatInviteLink"]
+#[answer = "ChatInviteLink"]
+#[function = "create_chat_invite_link"]
+pub struct CreateChatInviteLink {
+    chat_id: ChatId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expire_date: Option<UnixTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    member_limit: Option<Integer>,
+}
+
+/// Use this method to edit a non-primary invite link created by the bot. The bot must be an
+/// administrator in the chat for this to work and must have the appropriate admin rights. Returns
+/// the edited invite link as a ChatInviteLink object.
+#[derive(TelegramFunction, Serialize)]
+#[call = "editChatInviteLink"]
+#[answer = "ChatInviteLink"]
+#[function = "edit_chat_invite_link"]
+pub struct EditChatInviteLink {
+    chat_id: ChatId,
+    invite_link: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expire_date: Option<UnixTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    member_limit: Option<Integer>,
+}
+
+/// Use this method to revoke an invite link created by the bot. If the primary link is revoked, a
+/// new link is automatically generated. Returns the revoked invite link as ChatInviteLink object.
+#[derive(TelegramFunction, Serialize)]
+#[call = "revokeChatInviteLink"]
+#[answer = "ChatInviteLink"]
+#[function = "revoke_chat_invite_link"]
+pub struct RevokeChatInviteLink {
+    chat_id: ChatId,
+    invite_link: String,
+}
+
+/// Use this method to set default chat permissions for all members. The bot must be an
+/// administrator in the group or a supergroup for this to work and must have the
+/// can_restrict_members admin rights. Returns True on success.
+#[derive(TelegramFunction, Serialize)]
+#[call = "setChatPermissions"]
+#[answer = "Boolean"]
+#[function = "set_chat_permissions"]
+pub struct SetChatPermissions {
+    chat_id: ChatId,
+    permissions: objects::ChatPermissions,
+}
+
+/// Use this method to set a custom title for an administrator in a supergroup promoted by the bot.
+/// Returns True on success.
+#[derive(TelegramFunction, Serialize)]
+#[call = "setChatAdministratorCustomTitle"]
+#[answer = "Boolean"]
+#[function = "set_chat_administrator_custom_title"]
+pub struct SetChatAdministratorCustomTitle {
+    chat_id: ChatId,
+    user_id: Integer,
+    custom_title: String,
+}
+
+/// Use this method to set a new profile photo for the chat. Photos can't be changed for private
+/// chats and have to be uploaded with the `file` method. The bot must be an administrator in the
+/// chat for this to work and must have the appropriate admin rights. Returns True on success.
+#[derive(TelegramFunction, Serialize)]
+#[call = "setChatPhoto"]
+#[answer = "Boolean"]
+#[function = "set_chat_photo"]
+pub struct SetChatPhoto {
+    chat_id: ChatId,
+}
+
+impl WrapperSetChatPhoto {
+    /// Uploads the new photo, the photo can't be given as an url or file id
+    pub fn file<S>(self, val: S) -> Self
+    where
+        S: TryInto<file::File>,
+    {
+        self.attach("photo", val)
+    }
 }
 
 /// Use this method to delete a chat photo. Photos can't be changed for private chats. The bot must
@@ -548,7 +1182,7 @@ pub struct ExportChatInviteLink {
 #[answer = "Boolean"]
 #[function = "delete_chat_photo"]
 pub struct DeleteChatPhoto {
-    chat_id: Integer,
+    chat_id: ChatId,
 }
 
 /// Use this method to change the title of a chat. Titles can't be changed for private chats. The
@@ -562,7 +1196,7 @@ pub struct DeleteChatPhoto {
 #[answer = "Boolean"]
 #[function = "set_chat_title"]
 pub struct SetChatTitle {
-    chat_id: Integer,
+    chat_id: ChatId,
     title: String,
 }
 
@@ -574,10 +1208,33 @@ pub struct SetChatTitle {
 #[answer = "Boolean"]
 #[function = "set_chat_description"]
 pub struct SetChatDescription {
-    chat_id: Integer,
+    chat_id: ChatId,
     description: String,
 }
 
+/// Use this method to set a new group sticker set for a supergroup. The bot must be an
+/// administrator in the chat for this to work and must have the appropriate admin rights. Returns
+/// True on success.
+#[derive(TelegramFunction, Serialize)]
+#[call = "setChatStickerSet"]
+#[answer = "Boolean"]
+#[function = "set_chat_sticker_set"]
+pub struct SetChatStickerSet {
+    chat_id: ChatId,
+    sticker_set_name: String,
+}
+
+/// Use this method to delete a group sticker set from a supergroup. The bot must be an
+/// administrator in the chat for this to work and must have the appropriate admin rights. Returns
+/// True on success.
+#[derive(TelegramFunction, Serialize)]
+#[call = "deleteChatStickerSet"]
+#[answer = "Boolean"]
+#[function = "delete_chat_sticker_set"]
+pub struct DeleteChatStickerSet {
+    chat_id: ChatId,
+}
+
 /// Use this method to pin a message in a supergroup or a channel. The bot must be an administrator
 /// in the chat for this to work and must have the ‘can_pin_messages’ admin right in the supergroup
 /// or ‘can_edit_messages’ admin right in the channel. Returns True on success.
@@ -586,7 +1243,7 @@ pub struct SetChatDescription {
 #[answer = "Boolean"]
 #[function = "pin_chat_message"]
 pub struct PinChatMessage {
-    chat_id: Integer,
+    chat_id: ChatId,
     message_id: Integer,
     disable_notification: Option<bool>,
 }
@@ -599,7 +1256,7 @@ pub struct PinChatMessage {
 #[answer = "Boolean"]
 #[function = "unpin_chat_message"]
 pub struct UnpinChatMessage {
-    chat_id: Integer,
+    chat_id: ChatId,
 }
 
 /// Use this method to get up to date information about the chat (current name of the user for
@@ -610,7 +1267,7 @@ pub struct UnpinChatMessage {
 #[answer = "Chat"]
 #[function = "get_chat"]
 pub struct GetChat {
-    chat_id: Integer,
+    chat_id: ChatId,
 }
 
 /// Use this method to get a list of administrators in a chat. On success, returns an Array of
@@ -622,7 +1279,7 @@ pub struct GetChat {
 #[answer = "Vector<objects::ChatMember>"]
 #[function = "unban_chat_administrators"]
 pub struct GetChatAdministrators {
-    chat_id: Integer,
+    chat_id: ChatId,
 }
 
 /// Use this method to get the number of members in a chat. Returns Int on success.
@@ -631,7 +1288,7 @@ pub struct GetChatAdministrators {
 #[answer = "Integer"]
 #[function = "get_chat_members_count"]
 pub struct GetChatMemberCounts {
-    chat_id: Integer,
+    chat_id: ChatId,
 }
 
 /// Use this method to get information about a member of a chat. Returns a ChatMember object on
@@ -641,10 +1298,43 @@ pub struct GetChatMemberCounts {
 #[answer = "ChatMember"]
 #[function = "get_chat_member"]
 pub struct GetChatMember {
-    chat_id: Integer,
+    chat_id: ChatId,
     user_id: Integer,
 }
 
+/// Use this method to change the list of the bot's commands. Returns True on success.
+#[derive(TelegramFunction, Serialize)]
+#[call = "setMyCommands"]
+#[answer = "Boolean"]
+#[function = "set_my_commands"]
+pub struct SetMyCommands {
+    commands: Vec<objects::BotCommand>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language_code: Option<String>,
+}
+
+/// Use this method to get the current list of the bot's commands. Returns Array of BotCommand on
+/// success.
+#[derive(TelegramFunction, Serialize)]
+#[call = "getMyCommands"]
+#[answer = "Vector<objects::BotCommand>"]
+#[function = "get_my_commands"]
+pub struct GetMyCommands {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language_code: Option<String>,
+}
+
+/// Use this method to delete the list of the bot's commands. After deletion, higher level commands
+/// will be shown to affected users. Returns True on success.
+#[derive(TelegramFunction, Serialize)]
+#[call = "deleteMyCommands"]
+#[answer = "Boolean"]
+#[function = "delete_my_commands"]
+pub struct DeleteMyCommands {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language_code: Option<String>,
+}
+
 /// Use this method to send answers to callback queries sent from inline keyboards. The answer will
 /// be displayed to the user as a notification at the top of the chat screen or as an alert. On
 /// success, True is returned.
@@ -695,13 +1385,15 @@ pub struct AnswerInlineQuery {
 pub struct EditMessageText {
     text: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    chat_id: Option<Integer>,
+    chat_id: Option<ChatId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     message_id: Option<Integer>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    inline_message_id: Option<Integer>,
+    inline_message_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parse_mode: Option<ParseMode>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    parse_mode: Option<String>,
+    entities: Option<Vec<objects::MessageEntity>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     disable_web_page_preview: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -717,15 +1409,15 @@ pub struct EditMessageText {
 #[function = "edit_message_caption"]
 pub struct EditMessageCaption {
     #[serde(skip_serializing_if = "Option::is_none")]
-    chat_id: Option<Integer>,
+    chat_id: Option<ChatId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     message_id: Option<Integer>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    inline_message_id: Option<Integer>,
+    inline_message_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     caption: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    parse_mode: Option<String>,
+    parse_mode: Option<ParseMode>,
     #[serde(skip_serializing_if = "Option::is_none")]
     reply_markup: Option<objects::InlineKeyboardMarkup>,
 }
@@ -739,11 +1431,81 @@ pub struct EditMessageCaption {
 #[function = "edit_message_reply_markup"]
 pub struct EditMessageReplyMarkup {
     #[serde(skip_serializing_if = "Option::is_none")]
-    chat_id: Option<Integer>,
+    chat_id: Option<ChatId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message_id: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inline_message_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_markup: Option<objects::InlineKeyboardMarkup>,
+}
+
+/// Use this method to edit animation, audio, document, photo, or video messages. The media can be
+/// uploaded with the `file` method. On success, if the edited message was sent by the bot, the
+/// edited Message is returned, otherwise True is returned.
+#[derive(TelegramFunction, Serialize)]
+#[call = "editMessageMedia"]
+#[answer = "EditResponse"]
+#[function = "edit_message_media"]
+pub struct EditMessageMedia {
+    media: objects::InputMedia,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chat_id: Option<ChatId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message_id: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inline_message_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_markup: Option<objects::InlineKeyboardMarkup>,
+}
+
+impl WrapperEditMessageMedia {
+    /// Uploads the file as the new content of the message
+    pub fn file<S>(mut self, val: S) -> Self
+    where
+        S: TryInto<file::File>,
+    {
+        self.inner.media.set_media("attach://upload");
+
+        self.attach("upload", val)
+    }
+}
+
+/// Use this method to edit live location messages. A location can be edited until its live_period
+/// expires or editing is explicitly disabled by a call to stopMessageLiveLocation. On success, if
+/// the edited message was sent by the bot, the edited Message is returned, otherwise True is
+/// returned.
+#[derive(TelegramFunction, Serialize)]
+#[call = "editMessageLiveLocation"]
+#[answer = "EditResponse"]
+#[function = "edit_message_live_location"]
+pub struct EditMessageLiveLocation {
+    latitude: f32,
+    longitude: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chat_id: Option<ChatId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message_id: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inline_message_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_markup: Option<objects::InlineKeyboardMarkup>,
+}
+
+/// Use this method to stop updating a live location message before live_period expires. On
+/// success, if the message was sent by the bot, the sent Message is returned, otherwise True is
+/// returned.
+#[derive(TelegramFunction, Serialize)]
+#[call = "stopMessageLiveLocation"]
+#[answer = "EditResponse"]
+#[function = "stop_message_live_location"]
+pub struct StopMessageLiveLocation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chat_id: Option<ChatId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     message_id: Option<Integer>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    inline_message_id: Option<Integer>,
+    inline_message_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     reply_markup: Option<objects::InlineKeyboardMarkup>,
 }
@@ -761,7 +1523,7 @@ pub struct EditMessageReplyMarkup {
 #[answer = "Boolean"]
 #[function = "delete_message"]
 pub struct DeleteMessage {
-    chat_id: Integer,
+    chat_id: ChatId,
     message_id: Integer,
 }
 
@@ -794,3 +1556,81 @@ pub struct AddStickerToSet {
     #[serde(skip_serializing_if = "Option::is_none")]
     png_sticker: Option<String>,
 }
+
+/// Use this method to send invoices. The prices are given in the smallest units of the currency.
+/// On success, the sent Message is returned.
+#[derive(TelegramFunction, Serialize)]
+#[call = "sendInvoice"]
+#[answer = "Message"]
+#[function = "invoice"]
+pub struct SendInvoice {
+    chat_id: ChatId,
+    title: String,
+    description: String,
+    payload: String,
+    provider_token: String,
+    currency: String,
+    prices: Vec<objects::LabeledPrice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_parameter: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    provider_data: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    photo_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    photo_size: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    photo_width: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    photo_height: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    need_name: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    need_phone_number: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    need_email: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    need_shipping_address: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    send_phone_number_to_provider: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    send_email_to_provider: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_flexible: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disable_notification: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_to_message_id: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_markup: Option<objects::InlineKeyboardMarkup>,
+}
+
+/// If you sent an invoice requesting a shipping address and the parameter is_flexible was
+/// specified, the Bot API will send a ShippingQuery to the bot. Use this method to reply to
+/// shipping queries. On success, True is returned.
+#[derive(TelegramFunction, Serialize)]
+#[call = "answerShippingQuery"]
+#[answer = "Boolean"]
+#[function = "answer_shipping_query"]
+pub struct AnswerShippingQuery {
+    shipping_query_id: String,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shipping_options: Option<Vec<objects::ShippingOption>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_message: Option<String>,
+}
+
+/// Once the user has confirmed their payment and shipping details, the Bot API sends the final
+/// confirmation in the form of a PreCheckoutQuery. Use this method to respond to such pre-checkout
+/// queries. The answer has to be sent within 10 seconds. On success, True is returned.
+#[derive(TelegramFunction, Serialize)]
+#[call = "answerPreCheckoutQuery"]
+#[answer = "Boolean"]
+#[function = "answer_pre_checkout_query"]
+pub struct AnswerPreCheckoutQuery {
+    pre_checkout_query_id: String,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_message: Option<String>,
+}
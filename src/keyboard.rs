@@ -0,0 +1,231 @@
+//! Builders for inline and reply keyboards
+//!
+//! Instead of nesting the buttons by hand, a keyboard can be built row by row. If a number of
+//! columns is set, a new row is started automatically when the current one is full.
+//!
+//! ```
+//! use telebot::keyboard::InlineKeyboard;
+//!
+//! let keyboard = InlineKeyboard::new()
+//!     .columns(2)
+//!     .button_callback("Yes", "yes")
+//!     .button_callback("No", "no")
+//!     .button_url("Help", "https://core.telegram.org/bots")
+//!     .build();
+//!
+//! assert_eq!(keyboard.inline_keyboard.len(), 2);
+//! ```
+
+use objects::{InlineKeyboardButton, InlineKeyboardMarkup, KeyboardButton, ReplyKeyboardMarkup};
+use functions::ReplyMarkup;
+use callback::CallbackData;
+use failure::Error;
+
+/// Appends a button to the last row, a new row is started if the last one is full
+fn push<T>(rows: &mut Vec<Vec<T>>, columns: Option<usize>, button: T) {
+    let full = match (rows.last(), columns) {
+        (Some(row), Some(columns)) => row.len() >= columns,
+        (Some(_), None) => false,
+        (None, _) => true,
+    };
+
+    if full {
+        rows.push(Vec::new());
+    }
+
+    rows.last_mut().unwrap().push(button);
+}
+
+/// Removes empty rows, which are created by calling `row` multiple times
+fn non_empty<T>(rows: Vec<Vec<T>>) -> Vec<Vec<T>> {
+    rows.into_iter().filter(|row| !row.is_empty()).collect()
+}
+
+/// A builder for an inline keyboard
+#[derive(Debug, Default)]
+pub struct InlineKeyboard {
+    rows: Vec<Vec<InlineKeyboardButton>>,
+    columns: Option<usize>,
+}
+
+impl InlineKeyboard {
+    pub fn new() -> InlineKeyboard {
+        InlineKeyboard::default()
+    }
+
+    /// Limits the number of buttons in a row, further buttons are wrapped into a new row
+    pub fn columns(mut self, columns: usize) -> Self {
+        self.columns = Some(columns.max(1));
+
+        self
+    }
+
+    /// Starts a new row
+    pub fn row(mut self) -> Self {
+        self.rows.push(Vec::new());
+
+        self
+    }
+
+    /// Appends an arbitrary button
+    pub fn button(mut self, button: InlineKeyboardButton) -> Self {
+        push(&mut self.rows, self.columns, button);
+
+        self
+    }
+
+    /// Appends a button which sends a callback query with the data
+    pub fn button_callback<S, D>(self, text: S, data: D) -> Self
+    where
+        S: Into<String>,
+        D: Into<String>,
+    {
+        self.button(InlineKeyboardButton::new(text.into()).callback_data(data))
+    }
+
+    /// Appends a button with typed callback data. Fails if the encoded data exceeds 64 bytes.
+    pub fn button_data<S, T>(self, text: S, data: &T) -> Result<Self, Error>
+    where
+        S: Into<String>,
+        T: CallbackData,
+    {
+        let data = data.encode()?;
+
+        Ok(self.button_callback(text, data))
+    }
+
+    /// Appends a button which opens the url
+    pub fn button_url<S, U>(self, text: S, url: U) -> Self
+    where
+        S: Into<String>,
+        U: Into<String>,
+    {
+        self.button(InlineKeyboardButton::new(text.into()).url(url))
+    }
+
+    /// Appends a button which lets the user choose a chat and inserts the inline query there
+    pub fn button_switch_inline<S, Q>(self, text: S, query: Q) -> Self
+    where
+        S: Into<String>,
+        Q: Into<String>,
+    {
+        self.button(InlineKeyboardButton::new(text.into()).switch_inline_query(query))
+    }
+
+    pub fn build(self) -> InlineKeyboardMarkup {
+        InlineKeyboardMarkup::new(non_empty(self.rows))
+    }
+}
+
+impl From<InlineKeyboard> for InlineKeyboardMarkup {
+    fn from(keyboard: InlineKeyboard) -> Self {
+        keyboard.build()
+    }
+}
+
+impl From<InlineKeyboard> for ReplyMarkup {
+    fn from(keyboard: InlineKeyboard) -> Self {
+        ReplyMarkup::InlineKeyboardMarkup(keyboard.build())
+    }
+}
+
+/// A builder for a custom reply keyboard
+#[derive(Debug, Default)]
+pub struct ReplyKeyboard {
+    rows: Vec<Vec<KeyboardButton>>,
+    columns: Option<usize>,
+    resize_keyboard: Option<bool>,
+    one_time_keyboard: Option<bool>,
+    selective: Option<bool>,
+}
+
+impl ReplyKeyboard {
+    pub fn new() -> ReplyKeyboard {
+        ReplyKeyboard::default()
+    }
+
+    /// Limits the number of buttons in a row, further buttons are wrapped into a new row
+    pub fn columns(mut self, columns: usize) -> Self {
+        self.columns = Some(columns.max(1));
+
+        self
+    }
+
+    /// Starts a new row
+    pub fn row(mut self) -> Self {
+        self.rows.push(Vec::new());
+
+        self
+    }
+
+    /// Appends a button which sends its text
+    pub fn button<S: Into<String>>(mut self, text: S) -> Self {
+        push(&mut self.rows, self.columns, KeyboardButton::new(text.into()));
+
+        self
+    }
+
+    /// Appends a button which sends the phone number of the user
+    pub fn request_contact<S: Into<String>>(mut self, text: S) -> Self {
+        push(
+            &mut self.rows,
+            self.columns,
+            KeyboardButton::new(text.into()).request_contact(true),
+        );
+
+        self
+    }
+
+    /// Appends a button which sends the location of the user
+    pub fn request_location<S: Into<String>>(mut self, text: S) -> Self {
+        push(
+            &mut self.rows,
+            self.columns,
+            KeyboardButton::new(text.into()).request_location(true),
+        );
+
+        self
+    }
+
+    /// Requests clients to resize the keyboard vertically for optimal fit
+    pub fn resize(mut self, resize: bool) -> Self {
+        self.resize_keyboard = Some(resize);
+
+        self
+    }
+
+    /// Requests clients to hide the keyboard as soon as it's been used
+    pub fn one_time(mut self, one_time: bool) -> Self {
+        self.one_time_keyboard = Some(one_time);
+
+        self
+    }
+
+    /// Shows the keyboard only to mentioned users and the sender of the replied message
+    pub fn selective(mut self, selective: bool) -> Self {
+        self.selective = Some(selective);
+
+        self
+    }
+
+    pub fn build(self) -> ReplyKeyboardMarkup {
+        ReplyKeyboardMarkup {
+            keyboard: non_empty(self.rows),
+            resize_keyboard: self.resize_keyboard,
+            one_time_keyboard: self.one_time_keyboard,
+            selective: self.selective,
+        }
+    }
+}
+
+impl From<ReplyKeyboard> for ReplyKeyboardMarkup {
+    fn from(keyboard: ReplyKeyboard) -> Self {
+        keyboard.build()
+    }
+}
+
+impl From<ReplyKeyboard> for ReplyMarkup {
+    fn from(keyboard: ReplyKeyboard) -> Self {
+        ReplyMarkup::ReplyKeyboardMarkup(keyboard.build())
+    }
+}
@@ -64,10 +64,10 @@ extern crate log;
 #[macro_use]
 extern crate serde_derive;
 
+extern crate base64;
 extern crate erased_serde;
 extern crate futures;
 extern crate hyper;
-extern crate hyper_multipart_rfc7578 as hyper_multipart;
 extern crate hyper_tls;
 extern crate native_tls;
 extern crate serde;
@@ -81,9 +81,15 @@ extern crate failure;
 pub use bot::RcBot;
 //pub use error::Error;
 pub use file::File;
+pub use callback::CallbackData;
 
 pub mod bot;
+pub mod callback;
 pub mod error;
 pub mod objects;
 pub mod functions;
 pub mod file;
+pub mod keyboard;
+pub mod menu;
+mod multipart;
+pub mod text;
@@ -0,0 +1,308 @@
+//! Paginated menus built from inline keyboards
+//!
+//! A menu is a list of items and submenus, which is rendered page by page into an inline keyboard.
+//! The navigation (previous/next page, submenus and back) is encoded in the callback data and
+//! handled by the bot itself: the message is edited in place and the callback query is answered.
+//! Only the selection of an item is forwarded to the stream returned by `RcBot::new_menu`.
+//!
+//! The callback data contains the id of the menu and the indices of the opened submenus, it has to
+//! fit into 64 bytes. This is checked when the menu is registered. A registered menu is owned by
+//! the bot, `RcBot::menu_markup` renders it again for every new message which shows the menu.
+//!
+//! ```
+//! use telebot::menu::Menu;
+//!
+//! let menu = Menu::new("settings")
+//!     .item("Notifications", "notify")
+//!     .submenu("Language", Menu::new("lang").item("English", "en").item("Deutsch", "de"));
+//!
+//! // send this markup together with a message to show the menu, or register the menu with
+//! // `bot.new_menu(menu)` and use `bot.menu_markup("settings")` instead
+//! let markup = menu.render();
+//! ```
+
+use bot::RcBot;
+use objects;
+use objects::InlineKeyboardMarkup;
+use keyboard::InlineKeyboard;
+use functions::{FunctionAnswerCallbackQuery, FunctionEditMessageReplyMarkup};
+use futures::Future;
+use failure::Error;
+use error::ErrorKind;
+use callback::CALLBACK_DATA_LIMIT;
+
+/// The prefix of all callback data which belongs to a menu
+pub const MENU_PREFIX: &str = "menu:";
+
+enum MenuEntry {
+    Item { text: String, data: String },
+    Submenu { text: String, menu: Menu },
+}
+
+/// A menu with items and nested submenus
+pub struct Menu {
+    id: String,
+    entries: Vec<MenuEntry>,
+    page_size: usize,
+    columns: usize,
+    prev_text: String,
+    next_text: String,
+    back_text: String,
+}
+
+impl Menu {
+    /// Creates a new menu. The id of a registered menu is part of the callback data and may not
+    /// contain ':' or '/'. Submenus are referenced by their position, so their ids are not sent.
+    pub fn new<S: Into<String>>(id: S) -> Menu {
+        Menu {
+            id: id.into(),
+            entries: Vec::new(),
+            page_size: 8,
+            columns: 1,
+            prev_text: "« Prev".into(),
+            next_text: "Next »".into(),
+            back_text: "Back".into(),
+        }
+    }
+
+    /// Appends an item, the data is returned when the item is selected
+    pub fn item<S, D>(mut self, text: S, data: D) -> Self
+    where
+        S: Into<String>,
+        D: Into<String>,
+    {
+        self.entries.push(MenuEntry::Item {
+            text: text.into(),
+            data: data.into(),
+        });
+
+        self
+    }
+
+    /// Appends a button which opens a submenu. The submenu shows a back button.
+    pub fn submenu<S: Into<String>>(mut self, text: S, menu: Menu) -> Self {
+        self.entries.push(MenuEntry::Submenu {
+            text: text.into(),
+            menu: menu,
+        });
+
+        self
+    }
+
+    /// Sets the number of entries on each page
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+
+        self
+    }
+
+    /// Sets the number of entries in each row
+    pub fn columns(mut self, columns: usize) -> Self {
+        self.columns = columns.max(1);
+
+        self
+    }
+
+    /// Sets the labels of the navigation buttons
+    pub fn labels<P, N, B>(mut self, prev: P, next: N, back: B) -> Self
+    where
+        P: Into<String>,
+        N: Into<String>,
+        B: Into<String>,
+    {
+        self.prev_text = prev.into();
+        self.next_text = next.into();
+        self.back_text = back.into();
+
+        self
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Renders the first page of the menu
+    pub fn render(&self) -> InlineKeyboardMarkup {
+        self.render_page(&self.id, 0, false)
+    }
+
+    fn render_page(&self, path: &str, page: usize, has_parent: bool) -> InlineKeyboardMarkup {
+        let pages = (self.entries.len() + self.page_size - 1) / self.page_size;
+        let page = page.min(pages.saturating_sub(1));
+        let start = page * self.page_size;
+
+        let mut keyboard = InlineKeyboard::new().columns(self.columns);
+
+        for (idx, entry) in self.entries.iter().enumerate().skip(start).take(self.page_size) {
+            keyboard = match *entry {
+                MenuEntry::Item { ref text, .. } => {
+                    keyboard.button_callback(text.as_str(), Action::Select(idx).encode(path))
+                }
+                MenuEntry::Submenu { ref text, .. } => keyboard.button_callback(
+                    text.as_str(),
+                    Action::Page(0).encode(&format!("{}/{}", path, idx)),
+                ),
+            };
+        }
+
+        keyboard = keyboard.row().columns(2);
+        if page > 0 {
+            keyboard = keyboard.button_callback(
+                self.prev_text.as_str(),
+                Action::Page(page - 1).encode(path),
+            );
+        }
+        if page + 1 < pages {
+            keyboard = keyboard.button_callback(
+                self.next_text.as_str(),
+                Action::Page(page + 1).encode(path),
+            );
+        }
+
+        if has_parent {
+            let parent = &path[..path.rfind('/').unwrap_or(0)];
+            keyboard = keyboard
+                .row()
+                .button_callback(self.back_text.as_str(), Action::Page(0).encode(parent));
+        }
+
+        keyboard.build()
+    }
+
+    /// Checks that the id is valid and that the callback data of every button, including the
+    /// buttons of the submenus, fits into 64 bytes
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.id.is_empty() || self.id.contains(':') || self.id.contains('/') {
+            return Err(Error::from(ErrorKind::MenuId));
+        }
+
+        self.validate_path(&self.id)
+    }
+
+    fn validate_path(&self, path: &str) -> Result<(), Error> {
+        // the last page and the last item produce the longest callback data of this menu
+        let pages = (self.entries.len() + self.page_size - 1) / self.page_size;
+        let longest = [
+            Action::Page(pages.saturating_sub(1)).encode(path),
+            Action::Select(self.entries.len().saturating_sub(1)).encode(path),
+        ];
+
+        if longest.iter().any(|data| data.len() > CALLBACK_DATA_LIMIT) {
+            return Err(Error::from(ErrorKind::CallbackDataTooLong));
+        }
+
+        for (idx, entry) in self.entries.iter().enumerate() {
+            if let MenuEntry::Submenu { ref menu, .. } = *entry {
+                menu.validate_path(&format!("{}/{}", path, idx))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Finds a submenu by the indices of the path, an empty path refers to this menu
+    fn find(&self, path: &[&str]) -> Option<&Menu> {
+        match path.split_first() {
+            Some((idx, rest)) => {
+                let idx = idx.parse::<usize>().ok()?;
+
+                match self.entries.get(idx) {
+                    Some(&MenuEntry::Submenu { ref menu, .. }) => menu.find(rest),
+                    _ => None,
+                }
+            }
+            None => Some(self),
+        }
+    }
+}
+
+/// The action of a menu button, encoded in the callback data
+enum Action {
+    Page(usize),
+    Select(usize),
+}
+
+impl Action {
+    fn encode(&self, path: &str) -> String {
+        match *self {
+            Action::Page(page) => format!("{}{}:p{}", MENU_PREFIX, path, page),
+            Action::Select(idx) => format!("{}{}:s{}", MENU_PREFIX, path, idx),
+        }
+    }
+
+    fn decode(data: &str) -> Option<(&str, Action)> {
+        if !data.starts_with(MENU_PREFIX) {
+            return None;
+        }
+
+        let data = &data[MENU_PREFIX.len()..];
+        let pos = data.rfind(':')?;
+        let (path, action) = (&data[..pos], &data[pos + 1..]);
+
+        if action.len() < 2 {
+            return None;
+        }
+
+        let value = action[1..].parse().ok()?;
+        match &action[..1] {
+            "p" => Some((path, Action::Page(value))),
+            "s" => Some((path, Action::Select(value))),
+            _ => None,
+        }
+    }
+}
+
+/// Handles a callback query of a registered menu. Navigation is handled in place, selected items
+/// are forwarded to the stream of the menu. Returns the query if it doesn't belong to a menu.
+pub fn route(bot: &RcBot, query: objects::CallbackQuery) -> Option<objects::CallbackQuery> {
+    let (path, action) = match query.data.as_ref().and_then(|data| Action::decode(data)) {
+        Some((path, action)) => (path.to_string(), action),
+        None => return Some(query),
+    };
+
+    let ids = path.split('/').collect::<Vec<_>>();
+    let menus = bot.inner.menus.borrow();
+    let &(ref root, ref sender) = match menus.get(ids[0]) {
+        Some(entry) => entry,
+        None => return Some(query),
+    };
+
+    let menu = match root.find(&ids[1..]) {
+        Some(menu) => menu,
+        None => {
+            warn!("Received a callback for the unknown menu {}", path);
+            return None;
+        }
+    };
+
+    match action {
+        Action::Page(page) => {
+            let markup = menu.render_page(&path, page, ids.len() > 1);
+
+            let mut edit = bot.edit_message_reply_markup().reply_markup(markup);
+            if let Some(ref msg) = query.message {
+                edit = edit.chat_id(msg.chat.id).message_id(msg.message_id);
+            } else if let Some(ref id) = query.inline_message_id {
+                edit = edit.inline_message_id(id.as_str());
+            }
+
+            let answer = bot.answer_callback_query(query.id.clone()).send();
+            bot.inner.handle.spawn(
+                edit.send()
+                    .join(answer)
+                    .map(|_| ())
+                    .map_err(|e| error!("Could not update the menu: {}", e)),
+            );
+        }
+        Action::Select(idx) => match menu.entries.get(idx) {
+            Some(&MenuEntry::Item { ref data, .. }) => {
+                sender
+                    .unbounded_send((bot.clone(), query, data.clone()))
+                    .unwrap_or_else(|e| error!("Error: {}", e));
+            }
+            _ => warn!("Received a callback for the unknown item {} of {}", idx, path),
+        },
+    }
+
+    None
+}
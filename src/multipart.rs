@@ -0,0 +1,216 @@
+//! A multipart/form-data encoder for file uploads
+//!
+//! Instead of reading the files in advance, the content is streamed while the request is sent.
+//! Files on the disk and readers are blocking, so they are read in small chunks on a separate
+//! thread and passed to the request through a bounded channel. Streams are polled asynchronously.
+//!
+//! Names are percent-encoded like browsers do (RFC 7578), so a filename can't inject headers.
+//! Without an explicit MIME type, the content type is guessed from the extension of the filename.
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+use std::thread;
+
+use failure::{Error, ResultExt};
+use error::ErrorKind;
+use file::File;
+use futures::{stream, Sink, Stream};
+use futures::sync::mpsc;
+use hyper;
+use hyper::{Chunk, Request};
+use hyper::header::ContentLength;
+use uuid::Uuid;
+
+/// The body of a multipart request
+pub type Body = Box<Stream<Item = Chunk, Error = hyper::Error>>;
+
+/// The size of a chunk, which is read from a file at once
+const CHUNK_SIZE: usize = 8192;
+
+/// The number of chunks, which are read ahead of the request
+const READ_AHEAD: usize = 4;
+
+/// A multipart form, which consists of text fields and files
+pub struct Form {
+    boundary: String,
+    parts: Vec<Body>,
+    // the length of the body, if it's known for every part
+    length: Option<u64>,
+}
+
+impl Form {
+    pub fn new() -> Form {
+        Form {
+            boundary: Uuid::new_v4().simple().to_string(),
+            parts: Vec::new(),
+            length: Some(0),
+        }
+    }
+
+    /// Adds a text field
+    pub fn add_text(&mut self, name: &str, value: &str) {
+        let part = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+            self.boundary,
+            encode_name(name),
+            value
+        );
+
+        self.push_bytes(part.into_bytes());
+    }
+
+    /// Adds a file, the content of the file is read while the request is sent
+    pub fn add_file(&mut self, field: &str, file: File) -> Result<(), Error> {
+        let (filename, mime, length, content) = match file {
+            File::Memory { name, source } => (name, None, None, read_stream(source)),
+            File::Disk { path } => {
+                let file = fs::File::open(&path).context(ErrorKind::NoFile)?;
+                let length = file.metadata().ok().map(|metadata| metadata.len());
+                let name = Path::new(&path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or(path.clone());
+
+                (name, None, length, read_stream(file))
+            }
+            File::Bytes {
+                name,
+                content,
+                mime,
+            } => {
+                let length = Some(content.len() as u64);
+                let content: Body = Box::new(stream::once::<_, hyper::Error>(Ok(Chunk::from(content))));
+
+                (name, mime, length, content)
+            }
+            File::Stream {
+                name,
+                source,
+                mime,
+                length,
+            } => {
+                let content: Body = Box::new(source.map_err(|e| {
+                    hyper::Error::from(io::Error::new(io::ErrorKind::Other, e.to_string()))
+                }));
+
+                (name, mime, length, content)
+            }
+        };
+
+        let header = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+            self.boundary,
+            encode_name(field),
+            encode_name(&filename),
+            mime.as_ref().map(|x| x.as_str()).unwrap_or_else(|| guess_mime(&filename))
+        );
+
+        self.push_bytes(header.into_bytes());
+        self.push_stream(content, length);
+        self.push_bytes(b"\r\n".to_vec());
+
+        Ok(())
+    }
+
+    /// Sets the body of the request together with the content type and length
+    pub fn set_body(mut self, req: &mut Request<Body>) {
+        let end = format!("--{}--\r\n", self.boundary);
+        self.push_bytes(end.into_bytes());
+
+        req.headers_mut().set_raw(
+            "Content-Type",
+            format!("multipart/form-data; boundary={}", self.boundary),
+        );
+        if let Some(length) = self.length {
+            req.headers_mut().set(ContentLength(length));
+        }
+
+        req.set_body(Box::new(stream::iter_ok::<_, hyper::Error>(self.parts).flatten()) as Body);
+    }
+
+    fn push_bytes(&mut self, bytes: Vec<u8>) {
+        let length = bytes.len() as u64;
+
+        self.push_stream(Box::new(stream::once::<_, hyper::Error>(Ok(Chunk::from(bytes)))), Some(length));
+    }
+
+    fn push_stream(&mut self, content: Body, length: Option<u64>) {
+        self.length = match (self.length, length) {
+            (Some(total), Some(length)) => Some(total + length),
+            _ => None,
+        };
+
+        self.parts.push(content);
+    }
+}
+
+/// Encodes a name for the Content-Disposition header. Quotes and line breaks are percent-encoded
+/// as described in RFC 7578, section 4.2.
+fn encode_name(name: &str) -> String {
+    name.replace('"', "%22").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Guesses the MIME type from the extension of a filename
+fn guess_mime(filename: &str) -> &'static str {
+    let extension = Path::new(filename)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mp3" => "audio/mpeg",
+        "m4a" => "audio/mp4",
+        "ogg" | "oga" => "audio/ogg",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "json" => "application/json",
+        "tgs" => "application/x-tgsticker",
+        "txt" => "text/plain",
+        "html" | "htm" => "text/html",
+        "csv" => "text/csv",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Reads a source in chunks on a separate thread, so the event loop isn't blocked by the disk or a
+/// slow reader. The thread stops when the source is exhausted or the request is dropped.
+fn read_stream<R: Read + Send + 'static>(mut source: R) -> Body {
+    let (sender, receiver) = mpsc::channel::<io::Result<Vec<u8>>>(READ_AHEAD);
+
+    thread::spawn(move || {
+        let mut sender = sender.wait();
+
+        loop {
+            let mut buf = vec![0; CHUNK_SIZE];
+            let chunk = match source.read(&mut buf) {
+                Ok(0) => break,
+                Ok(len) => {
+                    buf.truncate(len);
+                    Ok(buf)
+                }
+                Err(e) => Err(e),
+            };
+
+            let failed = chunk.is_err();
+            if sender.send(chunk).is_err() || failed {
+                break;
+            }
+        }
+    });
+
+    Box::new(receiver.then(|chunk| match chunk {
+        Ok(Ok(buf)) => Ok(Chunk::from(buf)),
+        Ok(Err(e)) => Err(hyper::Error::from(e)),
+        Err(()) => Err(hyper::Error::from(io::Error::new(
+            io::ErrorKind::Other,
+            "The file reader stopped unexpectedly",
+        ))),
+    }))
+}
@@ -12,6 +12,66 @@ pub type NotImplemented = ();
 
 use erased_serde::Serialize;
 use uuid::Uuid;
+use functions::ParseMode;
+use text;
+use callback::CallbackData;
+use failure::Error;
+use error::ErrorKind;
+use bot::RcBot;
+use futures::Future;
+use std::io::Write;
+
+/// Implements a string backed enum, which is (de)serialized from the Telegram names of the variants.
+/// Unknown names are kept in the `Unknown` variant, so new values of the API don't break the
+/// parsing.
+macro_rules! string_enum {
+    ($name:ident { $($variant:ident => $value:tt,)* }) => {
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum $name {
+            $($variant,)*
+            Unknown(String),
+        }
+
+        impl $name {
+            /// Returns the name used by the Telegram API
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(&$name::$variant => $value,)*
+                    &$name::Unknown(ref value) => value,
+                }
+            }
+        }
+
+        impl<'a> From<&'a str> for $name {
+            fn from(value: &'a str) -> Self {
+                match value {
+                    $($value => $name::$variant,)*
+                    _ => $name::Unknown(value.into()),
+                }
+            }
+        }
+
+        impl ::serde::Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: ::serde::Serializer,
+            {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> ::serde::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: ::serde::Deserializer<'de>,
+            {
+                let value = String::deserialize(deserializer)?;
+
+                Ok($name::from(value.as_str()))
+            }
+        }
+    }
+}
 
 #[derive(Deserialize, Debug)]
 #[serde(untagged)]
@@ -23,6 +83,39 @@ pub enum EditResponse {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Link(pub String);
 
+/// Describes actions that a non-administrator user is allowed to take in a chat.
+#[derive(setter, Serialize, Deserialize, Debug, Clone)]
+pub struct ChatPermissions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_send_messages: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_send_media_messages: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_send_polls: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_send_other_messages: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_add_web_page_previews: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_change_info: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_invite_users: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_pin_messages: Option<bool>,
+}
+
+/// Represents an invite link for a chat.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ChatInviteLink {
+    pub invite_link: String,
+    pub creator: User,
+    pub is_primary: bool,
+    pub is_revoked: bool,
+    pub name: Option<String>,
+    pub expire_date: Option<Integer>,
+    pub member_limit: Option<Integer>,
+}
+
 /// This object represents a Telegram user or bot.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct User {
@@ -32,28 +125,137 @@ pub struct User {
     pub username: Option<String>,
 }
 
+string_enum!(ChatKind {
+    Private => "private",
+    Group => "group",
+    Supergroup => "supergroup",
+    Channel => "channel",
+});
+
 /// This object represents a chat.
 #[derive(Deserialize, Debug)]
 pub struct Chat {
     pub id: Integer,
     #[serde(rename = "type")]
-    pub kind: String,
+    pub kind: ChatKind,
     pub title: Option<String>,
     pub username: Option<String>,
     pub first_name: Option<String>,
     pub last_name: Option<String>,
     pub all_members_are_administrators: Option<bool>,
+    pub permissions: Option<ChatPermissions>,
+}
+
+impl Chat {
+    /// Returns true for one-on-one conversations
+    pub fn is_private(&self) -> bool {
+        self.kind == ChatKind::Private
+    }
+
+    /// Returns true for groups and supergroups
+    pub fn is_group(&self) -> bool {
+        self.kind == ChatKind::Group || self.kind == ChatKind::Supergroup
+    }
+
+    /// Returns true for supergroups
+    pub fn is_supergroup(&self) -> bool {
+        self.kind == ChatKind::Supergroup
+    }
+
+    /// Returns true for channels
+    pub fn is_channel(&self) -> bool {
+        self.kind == ChatKind::Channel
+    }
+}
+
+/// Unique identifier for the target chat or username of the target channel (in the format
+/// @channelusername)
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChatId {
+    Id(Integer),
+    Username(String),
+}
+
+impl ::serde::Serialize for ChatId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        match self {
+            &ChatId::Id(id) => serializer.serialize_i64(id),
+            &ChatId::Username(ref name) => serializer.serialize_str(name),
+        }
+    }
+}
+
+impl From<Integer> for ChatId {
+    fn from(id: Integer) -> Self {
+        ChatId::Id(id)
+    }
+}
+
+impl<'a> From<&'a str> for ChatId {
+    fn from(name: &'a str) -> Self {
+        ChatId::from(String::from(name))
+    }
+}
+
+/// Parses a numeric id (e.g. read from a config file) or a username, the '@' of a username is
+/// optional
+impl From<String> for ChatId {
+    fn from(name: String) -> Self {
+        if let Ok(id) = name.trim().parse::<Integer>() {
+            ChatId::Id(id)
+        } else if name.starts_with('@') {
+            ChatId::Username(name)
+        } else {
+            ChatId::Username(format!("@{}", name))
+        }
+    }
+}
+
+impl<'a> From<&'a Chat> for ChatId {
+    fn from(chat: &'a Chat) -> Self {
+        ChatId::Id(chat.id)
+    }
+}
+
+string_enum!(MessageEntityKind {
+    Mention => "mention",
+    Hashtag => "hashtag",
+    Cashtag => "cashtag",
+    BotCommand => "bot_command",
+    Url => "url",
+    Email => "email",
+    PhoneNumber => "phone_number",
+    Bold => "bold",
+    Italic => "italic",
+    Underline => "underline",
+    Strikethrough => "strikethrough",
+    Spoiler => "spoiler",
+    Code => "code",
+    Pre => "pre",
+    TextLink => "text_link",
+    TextMention => "text_mention",
+});
+
+/// This object represents a unique message identifier.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct MessageId {
+    pub message_id: Integer,
 }
 
 /// This object represents one special entity in a text message. For example, hashtags, usernames,
 /// URLs, etc.
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MessageEntity {
     #[serde(rename = "type")]
-    pub kind: String,
+    pub kind: MessageEntityKind,
     pub offset: Integer,
     pub length: Integer,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub user: Option<User>,
 }
 
@@ -79,6 +281,12 @@ pub struct Message {
     pub sticker: Option<Sticker>,
     pub video: Option<Video>,
     pub voice: Option<Voice>,
+    pub animation: Option<Animation>,
+    pub video_note: Option<VideoNote>,
+    pub dice: Option<Dice>,
+    pub poll: Option<Poll>,
+    pub invoice: Option<Invoice>,
+    pub successful_payment: Option<SuccessfulPayment>,
     pub caption: Option<String>,
     pub contact: Option<Contact>,
     pub location: Option<Location>,
@@ -96,6 +304,216 @@ pub struct Message {
     pub pinned_message: Option<Box<Message>>,
 }
 
+/// The content of a message, as returned by `Message::kind`
+#[derive(Debug)]
+pub enum MessageKind<'a> {
+    Text(&'a str),
+    Photo(&'a [PhotoSize]),
+    Audio(&'a Audio),
+    Document(&'a Document),
+    Sticker(&'a Sticker),
+    Video(&'a Video),
+    Voice(&'a Voice),
+    Animation(&'a Animation),
+    VideoNote(&'a VideoNote),
+    Dice(&'a Dice),
+    Poll(&'a Poll),
+    Invoice(&'a Invoice),
+    SuccessfulPayment(&'a SuccessfulPayment),
+    Location(&'a Location),
+    Venue(&'a Venue),
+    Contact(&'a Contact),
+    Game(&'a Game),
+    NewChatMember(&'a User),
+    LeftChatMember(&'a User),
+    NewChatTitle(&'a str),
+    NewChatPhoto(&'a [PhotoSize]),
+    DeleteChatPhoto,
+    GroupChatCreated,
+    SupergroupChatCreated,
+    ChannelChatCreated,
+    MigrateToChatId(Integer),
+    MigrateFromChatId(Integer),
+    PinnedMessage(&'a Message),
+    Unknown,
+}
+
+impl<'a> MessageKind<'a> {
+    /// Returns true if the message is a service message (e.g. a new member joined the chat)
+    pub fn is_service(&self) -> bool {
+        match *self {
+            MessageKind::NewChatMember(_)
+            | MessageKind::LeftChatMember(_)
+            | MessageKind::NewChatTitle(_)
+            | MessageKind::NewChatPhoto(_)
+            | MessageKind::DeleteChatPhoto
+            | MessageKind::GroupChatCreated
+            | MessageKind::SupergroupChatCreated
+            | MessageKind::ChannelChatCreated
+            | MessageKind::MigrateToChatId(_)
+            | MessageKind::MigrateFromChatId(_)
+            | MessageKind::PinnedMessage(_)
+            | MessageKind::SuccessfulPayment(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the message contains a file (photo, audio, document, etc.)
+    pub fn is_media(&self) -> bool {
+        match *self {
+            MessageKind::Photo(_)
+            | MessageKind::Audio(_)
+            | MessageKind::Document(_)
+            | MessageKind::Sticker(_)
+            | MessageKind::Video(_)
+            | MessageKind::Voice(_)
+            | MessageKind::Animation(_)
+            | MessageKind::VideoNote(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Message {
+    /// Classifies the content of the message. A venue also contains a location, but is returned
+    /// as `MessageKind::Venue`.
+    pub fn kind(&self) -> MessageKind {
+        if let Some(ref text) = self.text {
+            MessageKind::Text(text)
+        } else if let Some(ref photo) = self.photo {
+            MessageKind::Photo(photo)
+        } else if let Some(ref audio) = self.audio {
+            MessageKind::Audio(audio)
+        } else if let Some(ref animation) = self.animation {
+            MessageKind::Animation(animation)
+        } else if let Some(ref document) = self.document {
+            MessageKind::Document(document)
+        } else if let Some(ref sticker) = self.sticker {
+            MessageKind::Sticker(sticker)
+        } else if let Some(ref video) = self.video {
+            MessageKind::Video(video)
+        } else if let Some(ref voice) = self.voice {
+            MessageKind::Voice(voice)
+        } else if let Some(ref video_note) = self.video_note {
+            MessageKind::VideoNote(video_note)
+        } else if let Some(ref dice) = self.dice {
+            MessageKind::Dice(dice)
+        } else if let Some(ref poll) = self.poll {
+            MessageKind::Poll(poll)
+        } else if let Some(ref invoice) = self.invoice {
+            MessageKind::Invoice(invoice)
+        } else if let Some(ref payment) = self.successful_payment {
+            MessageKind::SuccessfulPayment(payment)
+        } else if let Some(ref venue) = self.venue {
+            MessageKind::Venue(venue)
+        } else if let Some(ref location) = self.location {
+            MessageKind::Location(location)
+        } else if let Some(ref contact) = self.contact {
+            MessageKind::Contact(contact)
+        } else if let Some(ref game) = self.game {
+            MessageKind::Game(game)
+        } else if let Some(ref user) = self.new_chat_member {
+            MessageKind::NewChatMember(user)
+        } else if let Some(ref user) = self.left_chat_member {
+            MessageKind::LeftChatMember(user)
+        } else if let Some(ref title) = self.new_chat_title {
+            MessageKind::NewChatTitle(title)
+        } else if let Some(ref photo) = self.new_chat_photo {
+            MessageKind::NewChatPhoto(photo)
+        } else if self.delete_chat_photo.unwrap_or(false) {
+            MessageKind::DeleteChatPhoto
+        } else if self.group_chat_created.unwrap_or(false) {
+            MessageKind::GroupChatCreated
+        } else if self.supergroup_chat_created.unwrap_or(false) {
+            MessageKind::SupergroupChatCreated
+        } else if self.channel_chat_created.unwrap_or(false) {
+            MessageKind::ChannelChatCreated
+        } else if let Some(id) = self.migrate_to_chat_id {
+            MessageKind::MigrateToChatId(id)
+        } else if let Some(id) = self.migrate_from_chat_id {
+            MessageKind::MigrateFromChatId(id)
+        } else if let Some(ref message) = self.pinned_message {
+            MessageKind::PinnedMessage(message)
+        } else {
+            MessageKind::Unknown
+        }
+    }
+
+    /// Returns the photo with the highest resolution, if the message contains a photo
+    pub fn largest_photo(&self) -> Option<&PhotoSize> {
+        self.photo.as_ref().and_then(|photos| largest_photo(photos))
+    }
+
+    /// Returns the photo whose resolution is closest to the requested one, if the message contains
+    /// a photo
+    pub fn closest_photo(&self, width: Integer, height: Integer) -> Option<&PhotoSize> {
+        self.photo
+            .as_ref()
+            .and_then(|photos| closest_photo(photos, width, height))
+    }
+
+    /// Returns the file of the message, for photos the largest size is chosen
+    pub fn media(&self) -> Option<&Downloadable> {
+        match self.kind() {
+            MessageKind::Photo(photos) => largest_photo(photos).map(|x| x as &Downloadable),
+            MessageKind::Audio(audio) => Some(audio),
+            MessageKind::Document(document) => Some(document),
+            MessageKind::Sticker(sticker) => Some(sticker),
+            MessageKind::Video(video) => Some(video),
+            MessageKind::Voice(voice) => Some(voice),
+            MessageKind::Animation(animation) => Some(animation),
+            MessageKind::VideoNote(video_note) => Some(video_note),
+            _ => None,
+        }
+    }
+
+    /// Downloads the file of the message into memory, for photos the largest size is chosen
+    pub fn download_media(&self, bot: &RcBot) -> Option<Box<Future<Item = (RcBot, Vec<u8>), Error = Error>>> {
+        self.media().map(|media| media.download(bot))
+    }
+
+    /// Downloads the file of the message and streams the content into the writer, for photos the
+    /// largest size is chosen
+    pub fn download_media_to<W>(
+        &self,
+        bot: &RcBot,
+        writer: W,
+    ) -> Option<Box<Future<Item = (RcBot, W, u64), Error = Error>>>
+    where
+        W: Write + 'static,
+    {
+        self.media().map(|media| {
+            Box::new(bot.download_file_to_writer(media.file_id().to_string(), writer))
+                as Box<Future<Item = (RcBot, W, u64), Error = Error>>
+        })
+    }
+
+    /// Returns the part of the text which is covered by the entity. The offsets of an entity are
+    /// measured in UTF-16 code units and can't be used to slice the text directly.
+    pub fn entity_text(&self, entity: &MessageEntity) -> Option<&str> {
+        self.text
+            .as_ref()
+            .and_then(|text| text::entity_text(text, entity))
+    }
+
+    /// Returns an iterator over all entities of the text together with the covered text
+    pub fn entities_text<'a>(&'a self) -> impl Iterator<Item = (&'a MessageEntity, &'a str)> + 'a {
+        self.entities
+            .iter()
+            .flat_map(|entities| entities.iter())
+            .filter_map(move |entity| self.entity_text(entity).map(|text| (entity, text)))
+    }
+
+    /// Renders the text and its entities into Markdown or HTML, e.g. to quote the message in
+    /// another message with the same formatting.
+    pub fn render_text(&self, mode: ParseMode) -> Option<String> {
+        self.text.as_ref().map(|text| match self.entities {
+            Some(ref entities) => text::render(text, entities, mode),
+            None => text::escape(text, mode),
+        })
+    }
+}
+
 #[derive(Deserialize, Debug)]
 pub struct Updates(pub Vec<Update>);
 
@@ -109,6 +527,23 @@ pub struct Update {
     pub inline_query: Option<InlineQuery>,
     pub chosen_inline_result: Option<()>,
     pub callback_query: Option<CallbackQuery>,
+    pub poll: Option<Poll>,
+    pub poll_answer: Option<PollAnswer>,
+    pub shipping_query: Option<ShippingQuery>,
+    pub pre_checkout_query: Option<PreCheckoutQuery>,
+}
+
+/// Contains information about the current status of a webhook.
+#[derive(Deserialize, Debug)]
+pub struct WebhookInfo {
+    pub url: String,
+    pub has_custom_certificate: bool,
+    pub pending_update_count: Integer,
+    pub ip_address: Option<String>,
+    pub last_error_date: Option<Integer>,
+    pub last_error_message: Option<String>,
+    pub max_connections: Option<Integer>,
+    pub allowed_updates: Option<Vec<String>>,
 }
 
 /// This object represents one size of a photo or a file / sticker thumbnail.
@@ -120,6 +555,52 @@ pub struct PhotoSize {
     pub file_size: Option<Integer>,
 }
 
+/// Returns the photo with the highest resolution
+pub fn largest_photo(photos: &[PhotoSize]) -> Option<&PhotoSize> {
+    photos.iter().max_by_key(|photo| photo.width * photo.height)
+}
+
+/// Returns the photo whose resolution is closest to the requested one
+pub fn closest_photo(photos: &[PhotoSize], width: Integer, height: Integer) -> Option<&PhotoSize> {
+    photos
+        .iter()
+        .min_by_key(|photo| (photo.width - width).abs() + (photo.height - height).abs())
+}
+
+/// A file which was sent to the bot and can be downloaded
+pub trait Downloadable {
+    /// Returns the identifier of the file, which can be used to download it
+    fn file_id(&self) -> &str;
+
+    /// Downloads the file into memory
+    fn download(&self, bot: &RcBot) -> Box<Future<Item = (RcBot, Vec<u8>), Error = Error>> {
+        Box::new(bot.download_file_to_vec(self.file_id().to_string()))
+    }
+
+    /// Downloads the file and streams the content into the writer
+    fn download_to<W>(&self, bot: &RcBot, writer: W) -> Box<Future<Item = (RcBot, W, u64), Error = Error>>
+    where
+        W: Write + 'static,
+        Self: Sized,
+    {
+        Box::new(bot.download_file_to_writer(self.file_id().to_string(), writer))
+    }
+}
+
+macro_rules! downloadable {
+    ($($name:ident),*) => {
+        $(
+            impl Downloadable for $name {
+                fn file_id(&self) -> &str {
+                    &self.file_id
+                }
+            }
+        )*
+    }
+}
+
+downloadable!(PhotoSize, Audio, Document, Animation, Sticker, Video, Voice, VideoNote);
+
 /// This object represents an audio file to be treated as music by the Telegram clients.
 #[derive(Deserialize, Debug)]
 pub struct Audio {
@@ -141,10 +622,13 @@ pub struct Document {
     pub file_size: Option<Integer>,
 }
 
-/// This object represents an animation file to be displayed in the message containing a game
+/// This object represents an animation file (GIF or H.264/MPEG-4 AVC video without sound).
 #[derive(Deserialize, Debug)]
 pub struct Animation {
     pub file_id: String,
+    pub width: Option<Integer>,
+    pub height: Option<Integer>,
+    pub duration: Option<Integer>,
     pub thumb: Option<PhotoSize>,
     pub file_name: Option<String>,
     pub mime_type: Option<String>,
@@ -200,6 +684,69 @@ pub struct Voice {
     pub file_size: Option<Integer>,
 }
 
+/// This object represents a video message (round video).
+#[derive(Deserialize, Debug)]
+pub struct VideoNote {
+    pub file_id: String,
+    pub length: Integer,
+    pub duration: Integer,
+    pub thumb: Option<PhotoSize>,
+    pub file_size: Option<Integer>,
+}
+
+/// This object represents an animated emoji that displays a random value.
+#[derive(Deserialize, Debug)]
+pub struct Dice {
+    pub emoji: String,
+    pub value: Integer,
+}
+
+string_enum!(PollKind {
+    Regular => "regular",
+    Quiz => "quiz",
+});
+
+/// This object contains information about one answer option in a poll.
+#[derive(Deserialize, Debug, Clone)]
+pub struct PollOption {
+    pub text: String,
+    pub voter_count: Integer,
+}
+
+/// This object represents an answer of a user in a non-anonymous poll.
+#[derive(Deserialize, Debug, Clone)]
+pub struct PollAnswer {
+    pub poll_id: String,
+    pub user: User,
+    pub option_ids: Vec<Integer>,
+}
+
+/// This object contains information about a poll.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Poll {
+    pub id: String,
+    pub question: String,
+    pub options: Vec<PollOption>,
+    pub total_voter_count: Integer,
+    pub is_closed: bool,
+    pub is_anonymous: bool,
+    #[serde(rename = "type")]
+    pub kind: PollKind,
+    pub allows_multiple_answers: bool,
+    pub correct_option_id: Option<Integer>,
+    pub explanation: Option<String>,
+    pub explanation_entities: Option<Vec<MessageEntity>>,
+    pub open_period: Option<Integer>,
+    pub close_date: Option<Integer>,
+}
+
+impl Poll {
+    /// Returns true if the poll is a quiz with exactly one correct answer
+    pub fn is_quiz(&self) -> bool {
+        self.kind == PollKind::Quiz
+    }
+}
+
 /// This object represents a phone contact.
 #[derive(Deserialize, Debug)]
 pub struct Contact {
@@ -214,6 +761,7 @@ pub struct Contact {
 pub struct Location {
     pub longitude: f32,
     pub latitude: f32,
+    pub live_period: Option<Integer>,
 }
 
 /// This object represents a venue.
@@ -245,7 +793,7 @@ pub struct File {
 
 /// This object represents a custom keyboard with reply options (see Introduction to bots for
 /// details and examples).
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(setter, Serialize, Deserialize, Debug)]
 pub struct ReplyKeyboardMarkup {
     pub keyboard: Vec<Vec<KeyboardButton>>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -259,7 +807,7 @@ pub struct ReplyKeyboardMarkup {
 /// This object represents one button of the reply keyboard. For simple text buttons String can be
 /// used instead of this object to specify text of the button. Optional fields are mutually
 /// exclusive.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(setter, Serialize, Deserialize, Debug)]
 pub struct KeyboardButton {
     pub text: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -272,7 +820,7 @@ pub struct KeyboardButton {
 /// keyboard and display the default letter-keyboard. By default, custom keyboards are displayed
 /// until a new keyboard is sent by a bot. An exception is made for one-time keyboards that are
 /// hidden immediately after the user presses a button (see ReplyKeyboardMarkup).
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(setter, Serialize, Deserialize, Debug)]
 pub struct ReplyKeyboardRemove {
     pub remove_keyboard: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -318,22 +866,63 @@ pub struct CallbackQuery {
     pub game_short_name: Option<String>,
 }
 
+impl CallbackQuery {
+    /// Decodes the callback data into a typed value
+    pub fn decode<T: CallbackData>(&self) -> Result<T, Error> {
+        match self.data {
+            Some(ref data) => T::decode(data),
+            None => Err(Error::from(ErrorKind::CallbackData)),
+        }
+    }
+}
+
 /// Upon receiving a message with this object, Telegram clients will display a reply interface to
 /// the user (act as if the user has selected the bot‘s message and tapped ’Reply'). This can be
 /// extremely useful if you want to create user-friendly step-by-step interfaces without having to
 /// sacrifice privacy mode.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(setter, Serialize, Deserialize, Debug)]
 pub struct ForceReply {
     pub force_reply: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub selective: Option<bool>,
 }
 
+string_enum!(ChatMemberStatus {
+    Creator => "creator",
+    Administrator => "administrator",
+    Member => "member",
+    Restricted => "restricted",
+    Left => "left",
+    Kicked => "kicked",
+});
+
 /// This object contains information about one member of the chat.
 #[derive(Deserialize, Debug)]
 pub struct ChatMember {
     pub user: User,
-    pub status: String,
+    pub status: ChatMemberStatus,
+}
+
+impl ChatMember {
+    /// Returns true if the member is the creator or an administrator of the chat
+    pub fn is_admin(&self) -> bool {
+        self.status == ChatMemberStatus::Creator || self.status == ChatMemberStatus::Administrator
+    }
+
+    /// Returns true if the member is currently in the chat
+    pub fn is_member(&self) -> bool {
+        match self.status {
+            ChatMemberStatus::Left | ChatMemberStatus::Kicked => false,
+            _ => true,
+        }
+    }
+}
+
+/// This object represents a bot command.
+#[derive(setter, Serialize, Deserialize, Debug, Clone)]
+pub struct BotCommand {
+    pub command: String,
+    pub description: String,
 }
 
 /// Contains information about why a request was unsuccessfull.
@@ -343,6 +932,150 @@ pub struct ResponseParameter {
     pub retry_after: Option<Integer>,
 }
 
+/// This object represents the content of a media message to be sent. The media can be a file_id,
+/// an url or a reference to an uploaded file in the form attach://<name>.
+#[derive(Serialize, Debug)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum InputMedia {
+    Photo(InputMediaPhoto),
+    Video(InputMediaVideo),
+    Animation(InputMediaAnimation),
+    Audio(InputMediaAudio),
+    Document(InputMediaDocument),
+}
+
+impl InputMedia {
+    /// Returns the file_id, url or attachment reference of the media
+    pub fn media(&self) -> &str {
+        match self {
+            &InputMedia::Photo(ref x) => &x.media,
+            &InputMedia::Video(ref x) => &x.media,
+            &InputMedia::Animation(ref x) => &x.media,
+            &InputMedia::Audio(ref x) => &x.media,
+            &InputMedia::Document(ref x) => &x.media,
+        }
+    }
+
+    /// Replaces the file_id, url or attachment reference of the media
+    pub fn set_media<S: Into<String>>(&mut self, val: S) {
+        match self {
+            &mut InputMedia::Photo(ref mut x) => x.media = val.into(),
+            &mut InputMedia::Video(ref mut x) => x.media = val.into(),
+            &mut InputMedia::Animation(ref mut x) => x.media = val.into(),
+            &mut InputMedia::Audio(ref mut x) => x.media = val.into(),
+            &mut InputMedia::Document(ref mut x) => x.media = val.into(),
+        }
+    }
+}
+
+impl From<InputMediaPhoto> for InputMedia {
+    fn from(f: InputMediaPhoto) -> Self {
+        InputMedia::Photo(f)
+    }
+}
+
+impl From<InputMediaVideo> for InputMedia {
+    fn from(f: InputMediaVideo) -> Self {
+        InputMedia::Video(f)
+    }
+}
+
+impl From<InputMediaAnimation> for InputMedia {
+    fn from(f: InputMediaAnimation) -> Self {
+        InputMedia::Animation(f)
+    }
+}
+
+impl From<InputMediaAudio> for InputMedia {
+    fn from(f: InputMediaAudio) -> Self {
+        InputMedia::Audio(f)
+    }
+}
+
+impl From<InputMediaDocument> for InputMedia {
+    fn from(f: InputMediaDocument) -> Self {
+        InputMedia::Document(f)
+    }
+}
+
+/// Represents a photo to be sent.
+#[derive(setter, Serialize, Debug)]
+pub struct InputMediaPhoto {
+    pub media: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse_mode: Option<ParseMode>,
+}
+
+/// Represents a video to be sent.
+#[derive(setter, Serialize, Debug)]
+pub struct InputMediaVideo {
+    pub media: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumb: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse_mode: Option<ParseMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub supports_streaming: Option<Boolean>,
+}
+
+/// Represents an animation file (GIF or H.264/MPEG-4 AVC video without sound) to be sent.
+#[derive(setter, Serialize, Debug)]
+pub struct InputMediaAnimation {
+    pub media: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumb: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse_mode: Option<ParseMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<Integer>,
+}
+
+/// Represents an audio file to be treated as music to be sent.
+#[derive(setter, Serialize, Debug)]
+pub struct InputMediaAudio {
+    pub media: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumb: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse_mode: Option<ParseMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<Integer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub performer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+/// Represents a general file to be sent.
+#[derive(setter, Serialize, Debug)]
+pub struct InputMediaDocument {
+    pub media: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumb: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse_mode: Option<ParseMode>,
+}
+
 /// A placeholder, currently holds no information. Use BotFather to set up your game.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CallbackGame;
@@ -779,7 +1512,7 @@ pub mod input_message_content {
     pub struct Text {
         pub message_text: String,
         #[serde(skip_serializing_if = "Option::is_none")]
-        pub parse_mode: Option<String>,
+        pub parse_mode: Option<::functions::ParseMode>,
         #[serde(skip_serializing_if = "Option::is_none")]
         pub disable_web_page_preview: Option<Boolean>,
     }
@@ -819,3 +1552,97 @@ pub struct ChosenInlineResult {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub inline_message_id: Option<String>,
 }
+
+/// This object represents a portion of the price for goods or services. The amount is given in
+/// the smallest units of the currency (e.g. cents).
+#[derive(setter, Serialize, Deserialize, Debug, Clone)]
+pub struct LabeledPrice {
+    pub label: String,
+    pub amount: Integer,
+}
+
+/// This object contains basic information about an invoice.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Invoice {
+    pub title: String,
+    pub description: String,
+    pub start_parameter: String,
+    pub currency: String,
+    pub total_amount: Integer,
+}
+
+/// This object represents a shipping address.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ShippingAddress {
+    pub country_code: String,
+    pub state: String,
+    pub city: String,
+    pub street_line1: String,
+    pub street_line2: String,
+    pub post_code: String,
+}
+
+/// This object represents information about an order.
+#[derive(Deserialize, Debug, Clone)]
+pub struct OrderInfo {
+    pub name: Option<String>,
+    pub phone_number: Option<String>,
+    pub email: Option<String>,
+    pub shipping_address: Option<ShippingAddress>,
+}
+
+/// This object represents one shipping option.
+#[derive(Serialize, Debug, Clone)]
+pub struct ShippingOption {
+    pub id: String,
+    pub title: String,
+    pub prices: Vec<LabeledPrice>,
+}
+
+impl ShippingOption {
+    pub fn new<S, T>(id: S, title: T, prices: Vec<LabeledPrice>) -> ShippingOption
+    where
+        S: Into<String>,
+        T: Into<String>,
+    {
+        ShippingOption {
+            id: id.into(),
+            title: title.into(),
+            prices: prices,
+        }
+    }
+}
+
+/// This object contains basic information about a successful payment.
+#[derive(Deserialize, Debug, Clone)]
+pub struct SuccessfulPayment {
+    pub currency: String,
+    pub total_amount: Integer,
+    pub invoice_payload: String,
+    pub shipping_option_id: Option<String>,
+    pub order_info: Option<OrderInfo>,
+    pub telegram_payment_charge_id: String,
+    pub provider_payment_charge_id: String,
+}
+
+/// This object contains information about an incoming shipping query.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ShippingQuery {
+    pub id: String,
+    pub from: User,
+    pub invoice_payload: String,
+    pub shipping_address: ShippingAddress,
+}
+
+/// This object contains information about an incoming pre-checkout query. It has to be answered
+/// within ten seconds, otherwise the payment is cancelled.
+#[derive(Deserialize, Debug, Clone)]
+pub struct PreCheckoutQuery {
+    pub id: String,
+    pub from: User,
+    pub currency: String,
+    pub total_amount: Integer,
+    pub invoice_payload: String,
+    pub shipping_option_id: Option<String>,
+    pub order_info: Option<OrderInfo>,
+}
@@ -0,0 +1,909 @@
+//! Helper functions to work with formatted text
+//!
+//! The offset and length of a message entity are measured in UTF-16 code units, therefore they
+//! can't be used to slice a Rust string directly. The functions in this module convert these
+//! positions and render a text with its entities back into Markdown or HTML.
+//!
+//! `FormattedText` builds a text without worrying about escaping user input:
+//!
+//! ```
+//! use telebot::text::FormattedText;
+//! use telebot::functions::ParseMode;
+//!
+//! let (text, mode) = FormattedText::new()
+//!     .bold("Warning: ")
+//!     .text("a_b < c")
+//!     .build(ParseMode::HTML);
+//!
+//! assert_eq!(text, "<b>Warning: </b>a_b &lt; c");
+//! ```
+
+use objects::{MessageEntity, MessageEntityKind, User};
+use functions::ParseMode;
+
+/// A builder for formatted text. The text is stored together with a list of entities and can be
+/// rendered into each parse mode with correct escaping, or sent with an explicit list of entities.
+#[derive(Debug, Clone, Default)]
+pub struct FormattedText {
+    text: String,
+    entities: Vec<MessageEntity>,
+}
+
+impl FormattedText {
+    pub fn new() -> FormattedText {
+        FormattedText::default()
+    }
+
+    /// Appends plain text
+    pub fn text<S: AsRef<str>>(mut self, text: S) -> Self {
+        self.text.push_str(text.as_ref());
+
+        self
+    }
+
+    pub fn bold<S: AsRef<str>>(self, text: S) -> Self {
+        self.entity(MessageEntityKind::Bold, text.as_ref(), None, None)
+    }
+
+    pub fn italic<S: AsRef<str>>(self, text: S) -> Self {
+        self.entity(MessageEntityKind::Italic, text.as_ref(), None, None)
+    }
+
+    pub fn code<S: AsRef<str>>(self, text: S) -> Self {
+        self.entity(MessageEntityKind::Code, text.as_ref(), None, None)
+    }
+
+    pub fn pre<S: AsRef<str>>(self, text: S) -> Self {
+        self.entity(MessageEntityKind::Pre, text.as_ref(), None, None)
+    }
+
+    pub fn link<S: AsRef<str>, U: Into<String>>(self, text: S, url: U) -> Self {
+        self.entity(MessageEntityKind::TextLink, text.as_ref(), Some(url.into()), None)
+    }
+
+    /// Mentions a user, which works also for users without a username
+    pub fn mention<S: AsRef<str>>(self, text: S, user: &User) -> Self {
+        self.entity(MessageEntityKind::TextMention, text.as_ref(), None, Some(user.clone()))
+    }
+
+    fn entity(
+        mut self,
+        kind: MessageEntityKind,
+        text: &str,
+        url: Option<String>,
+        user: Option<User>,
+    ) -> Self {
+        let offset = self.text.encode_utf16().count();
+        let length = text.encode_utf16().count();
+
+        self.text.push_str(text);
+        self.entities.push(MessageEntity {
+            kind: kind,
+            offset: offset as i64,
+            length: length as i64,
+            url: url,
+            user: user,
+        });
+
+        self
+    }
+
+    /// Returns the unformatted text, which is sent together with `entities`
+    pub fn plain_text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the entities of the text
+    pub fn entities(&self) -> &[MessageEntity] {
+        &self.entities
+    }
+
+    /// Renders the text into the parse mode and returns it together with the parse mode, ready
+    /// for `message` or `edit_message_text`
+    pub fn build(&self, mode: ParseMode) -> (String, ParseMode) {
+        (render(&self.text, &self.entities, mode.clone()), mode)
+    }
+}
+
+/// Converts an offset in UTF-16 code units to a byte offset in the string. Returns None if the
+/// offset is out of range or points into the middle of a character.
+pub fn utf16_to_byte(text: &str, offset: usize) -> Option<usize> {
+    let mut units = 0;
+
+    for (idx, ch) in text.char_indices() {
+        if units == offset {
+            return Some(idx);
+        } else if units > offset {
+            return None;
+        }
+
+        units += ch.len_utf16();
+    }
+
+    if units == offset {
+        Some(text.len())
+    } else {
+        None
+    }
+}
+
+/// Returns the byte range of an entity in the text
+pub fn entity_range(text: &str, entity: &MessageEntity) -> Option<(usize, usize)> {
+    if entity.offset < 0 || entity.length < 0 {
+        return None;
+    }
+
+    let start = utf16_to_byte(text, entity.offset as usize)?;
+    let end = utf16_to_byte(text, (entity.offset + entity.length) as usize)?;
+
+    Some((start, end))
+}
+
+/// Returns the part of the text which is covered by the entity
+pub fn entity_text<'a>(text: &'a str, entity: &MessageEntity) -> Option<&'a str> {
+    entity_range(text, entity).map(|(start, end)| &text[start..end])
+}
+
+/// Escapes all characters which have a special meaning in the parse mode. A custom parse mode is
+/// treated as plain text.
+pub fn escape(text: &str, mode: ParseMode) -> String {
+    let mut result = String::with_capacity(text.len());
+
+    for ch in text.chars() {
+        escape_char(&mut result, ch, &mode);
+    }
+
+    result
+}
+
+fn escape_char(result: &mut String, ch: char, mode: &ParseMode) {
+    match *mode {
+        ParseMode::HTML => match ch {
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '&' => result.push_str("&amp;"),
+            '"' => result.push_str("&quot;"),
+            ch => result.push(ch),
+        },
+        ParseMode::Markdown => match ch {
+            '_' | '*' | '`' | '[' => {
+                result.push('\\');
+                result.push(ch);
+            }
+            ch => result.push(ch),
+        },
+        ParseMode::MarkdownV2 => match ch {
+            '_' | '*' | '[' | ']' | '(' | ')' | '~' | '`' | '>' | '#' | '+' | '-' | '=' | '|'
+            | '{' | '}' | '.' | '!' | '\\' => {
+                result.push('\\');
+                result.push(ch);
+            }
+            ch => result.push(ch),
+        },
+        ParseMode::Text | ParseMode::Custom(_) => result.push(ch),
+    }
+}
+
+/// Renders a text with its entities into the format of the parse mode. Entities which are not
+/// supported by the parse mode are rendered as plain text.
+///
+/// The legacy Markdown mode is lossy: it supports neither nested entities nor escapes inside of
+/// an entity. Nested entities are rendered as plain text and an entity is interrupted by escaped
+/// characters (e.g. `_snake_\__case_`). Prefer MarkdownV2 or HTML to keep the formatting.
+pub fn render(text: &str, entities: &[MessageEntity], mode: ParseMode) -> String {
+    let legacy = mode == ParseMode::Markdown;
+
+    let mut ranges = entities
+        .iter()
+        .filter_map(|entity| entity_range(text, entity).map(|(start, end)| (start, end, entity)))
+        // unsupported entities would hide the entities nested in them in legacy Markdown
+        .filter(|&(_, _, entity)| !legacy || !open_tag(entity, &mode).is_empty())
+        .collect::<Vec<_>>();
+
+    // outer entities have to be opened first
+    ranges.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+
+    let mut result = String::with_capacity(text.len());
+    // the end of each open entity and whether its opening tag was already written
+    let mut stack: Vec<(usize, &MessageEntity, bool)> = Vec::new();
+    let mut ranges = ranges.into_iter().peekable();
+    // the end of the last written italic delimiter
+    let mut italic = None;
+
+    for (idx, ch) in text.char_indices() {
+        while stack.last().map(|&(end, _, _)| end <= idx).unwrap_or(false) {
+            let (_, entity, opened) = stack.pop().unwrap();
+            if opened {
+                push_tag(&mut result, &close_tag(entity, &mode), &mode, &mut italic);
+            }
+        }
+
+        while ranges.peek().map(|&(start, _, _)| start <= idx).unwrap_or(false) {
+            let (_, end, entity) = ranges.next().unwrap();
+
+            if legacy {
+                // only the outermost entity is kept, it is opened with its first character
+                if stack.is_empty() {
+                    stack.push((end, entity, false));
+                }
+            } else {
+                push_tag(&mut result, &open_tag(entity, &mode), &mode, &mut italic);
+                stack.push((end, entity, true));
+            }
+        }
+
+        // the content of code blocks can't be escaped in Markdown and only ` and \ have to be
+        // escaped in MarkdownV2
+        let in_code = stack.iter().any(|&(_, entity, _)| is_code(entity));
+
+        match mode {
+            ParseMode::Markdown => {
+                let mut escaped = String::new();
+                if in_code {
+                    escaped.push(ch);
+                } else {
+                    escape_char(&mut escaped, ch, &mode);
+                }
+
+                // escapes are not allowed inside of an entity, therefore the entity is closed
+                // before an escaped character and reopened afterwards
+                let is_escaped = escaped.len() != ch.len_utf8();
+                if let Some(&mut (_, entity, ref mut opened)) = stack.last_mut() {
+                    if is_escaped && *opened {
+                        result.push_str(&close_tag(entity, &mode));
+                        *opened = false;
+                    } else if !is_escaped && !*opened {
+                        result.push_str(&open_tag(entity, &mode));
+                        *opened = true;
+                    }
+                }
+
+                result.push_str(&escaped);
+            }
+            ParseMode::MarkdownV2 if in_code => {
+                if ch == '`' || ch == '\\' {
+                    result.push('\\');
+                }
+                result.push(ch);
+            }
+            _ => escape_char(&mut result, ch, &mode),
+        }
+    }
+
+    while let Some((_, entity, opened)) = stack.pop() {
+        if opened {
+            push_tag(&mut result, &close_tag(entity, &mode), &mode, &mut italic);
+        }
+    }
+
+    result
+}
+
+/// Appends a tag to the rendered text. `__` is always read as underline in MarkdownV2, therefore an
+/// italic delimiter followed by another underscore is separated by \r, which Telegram ignores.
+fn push_tag(result: &mut String, tag: &str, mode: &ParseMode, italic: &mut Option<usize>) {
+    if *mode == ParseMode::MarkdownV2 && tag.starts_with('_') && *italic == Some(result.len()) {
+        result.push('\r');
+    }
+
+    result.push_str(tag);
+
+    if tag == "_" {
+        *italic = Some(result.len());
+    }
+}
+
+fn is_code(entity: &MessageEntity) -> bool {
+    entity.kind == MessageEntityKind::Code || entity.kind == MessageEntityKind::Pre
+}
+
+fn open_tag(entity: &MessageEntity, mode: &ParseMode) -> String {
+    match *mode {
+        ParseMode::HTML => match entity.kind {
+            MessageEntityKind::Bold => "<b>".into(),
+            MessageEntityKind::Italic => "<i>".into(),
+            MessageEntityKind::Underline => "<u>".into(),
+            MessageEntityKind::Strikethrough => "<s>".into(),
+            MessageEntityKind::Spoiler => "<tg-spoiler>".into(),
+            MessageEntityKind::Code => "<code>".into(),
+            MessageEntityKind::Pre => "<pre>".into(),
+            MessageEntityKind::TextLink => match entity.url {
+                Some(ref url) => format!("<a href=\"{}\">", escape(url, ParseMode::HTML)),
+                None => String::new(),
+            },
+            MessageEntityKind::TextMention => match entity.user {
+                Some(ref user) => format!("<a href=\"tg://user?id={}\">", user.id),
+                None => String::new(),
+            },
+            _ => String::new(),
+        },
+        ParseMode::Markdown => match entity.kind {
+            MessageEntityKind::Bold => "*".into(),
+            MessageEntityKind::Italic => "_".into(),
+            MessageEntityKind::Code => "`".into(),
+            MessageEntityKind::Pre => "```\n".into(),
+            MessageEntityKind::TextLink if entity.url.is_some() => "[".into(),
+            MessageEntityKind::TextMention if entity.user.is_some() => "[".into(),
+            _ => String::new(),
+        },
+        ParseMode::MarkdownV2 => match entity.kind {
+            MessageEntityKind::Bold => "*".into(),
+            MessageEntityKind::Italic => "_".into(),
+            MessageEntityKind::Underline => "__".into(),
+            MessageEntityKind::Strikethrough => "~".into(),
+            MessageEntityKind::Spoiler => "||".into(),
+            MessageEntityKind::Code => "`".into(),
+            MessageEntityKind::Pre => "```\n".into(),
+            MessageEntityKind::TextLink if entity.url.is_some() => "[".into(),
+            MessageEntityKind::TextMention if entity.user.is_some() => "[".into(),
+            _ => String::new(),
+        },
+        ParseMode::Text | ParseMode::Custom(_) => String::new(),
+    }
+}
+
+fn close_tag(entity: &MessageEntity, mode: &ParseMode) -> String {
+    match *mode {
+        ParseMode::HTML => match entity.kind {
+            MessageEntityKind::Bold => "</b>".into(),
+            MessageEntityKind::Italic => "</i>".into(),
+            MessageEntityKind::Underline => "</u>".into(),
+            MessageEntityKind::Strikethrough => "</s>".into(),
+            MessageEntityKind::Spoiler => "</tg-spoiler>".into(),
+            MessageEntityKind::Code => "</code>".into(),
+            MessageEntityKind::Pre => "</pre>".into(),
+            MessageEntityKind::TextLink if entity.url.is_some() => "</a>".into(),
+            MessageEntityKind::TextMention if entity.user.is_some() => "</a>".into(),
+            _ => String::new(),
+        },
+        ParseMode::Markdown => match entity.kind {
+            MessageEntityKind::Bold => "*".into(),
+            MessageEntityKind::Italic => "_".into(),
+            MessageEntityKind::Code => "`".into(),
+            MessageEntityKind::Pre => "\n```".into(),
+            // the url can't be escaped, the link would end with the first parenthesis
+            MessageEntityKind::TextLink => match entity.url {
+                Some(ref url) => format!("]({})", url.replace(')', "%29")),
+                None => String::new(),
+            },
+            MessageEntityKind::TextMention => match entity.user {
+                Some(ref user) => format!("](tg://user?id={})", user.id),
+                None => String::new(),
+            },
+            _ => String::new(),
+        },
+        ParseMode::MarkdownV2 => match entity.kind {
+            MessageEntityKind::Bold => "*".into(),
+            MessageEntityKind::Italic => "_".into(),
+            MessageEntityKind::Underline => "__".into(),
+            MessageEntityKind::Strikethrough => "~".into(),
+            MessageEntityKind::Spoiler => "||".into(),
+            MessageEntityKind::Code => "`".into(),
+            MessageEntityKind::Pre => "\n```".into(),
+            // inside of the link only ) and \ have to be escaped
+            MessageEntityKind::TextLink => match entity.url {
+                Some(ref url) => format!("]({})", url.replace('\\', "\\\\").replace(')', "\\)")),
+                None => String::new(),
+            },
+            MessageEntityKind::TextMention => match entity.user {
+                Some(ref user) => format!("](tg://user?id={})", user.id),
+                None => String::new(),
+            },
+            _ => String::new(),
+        },
+        ParseMode::Text | ParseMode::Custom(_) => String::new(),
+    }
+}
+
+/// The maximum length of a text message
+pub const MESSAGE_LIMIT: usize = 4096;
+
+/// The maximum length of a caption
+pub const CAPTION_LIMIT: usize = 1024;
+
+/// Splits a text into chunks of at most `limit` UTF-16 code units. The text is preferably split
+/// between paragraphs, lines and then words outside of Markdown or HTML entities. An entity which
+/// exceeds the limit is closed at the end of a chunk and reopened at the beginning of the next
+/// one, only links are cut at the limit.
+pub fn split(text: &str, mode: ParseMode, limit: usize) -> Vec<String> {
+    let (points, tags) = markup_points(text, &mode);
+    let lengths = tags
+        .iter()
+        .map(|&(ref reopen, ref close)| {
+            (reopen.encode_utf16().count(), close.encode_utf16().count())
+        })
+        .collect::<Vec<_>>();
+
+    split_ranges(text, &points, &lengths, limit)
+        .into_iter()
+        .map(|(start, end)| {
+            let (ref reopen, _) = tags[points[start].unwrap_or(0)];
+            let (_, ref close) = tags[points[end].unwrap_or(0)];
+
+            format!("{}{}{}", reopen, &text[start..end], close)
+        })
+        .collect()
+}
+
+/// Splits a text with explicit entities like `split`. The entities are moved to the chunk which
+/// contains them and their offsets are adjusted. An entity which exceeds the limit is cut into
+/// the chunks it covers.
+pub fn split_entities(
+    text: &str,
+    entities: &[MessageEntity],
+    limit: usize,
+) -> Vec<(String, Vec<MessageEntity>)> {
+    let ranges = entities
+        .iter()
+        .filter_map(|entity| entity_range(text, entity))
+        .collect::<Vec<_>>();
+
+    let points = (0..text.len() + 1)
+        .map(|idx| {
+            if text.is_char_boundary(idx)
+                && !ranges.iter().any(|&(start, end)| start < idx && idx < end)
+            {
+                Some(0)
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    split_ranges(text, &points, &[(0, 0)], limit)
+        .into_iter()
+        .map(|(start, end)| {
+            let offset = text[..start].encode_utf16().count() as i64;
+            let length = text[start..end].encode_utf16().count() as i64;
+
+            let entities = entities
+                .iter()
+                .filter_map(|entity| {
+                    let start = entity.offset.max(offset);
+                    let end = (entity.offset + entity.length).min(offset + length);
+                    if start >= end {
+                        return None;
+                    }
+
+                    let mut entity = entity.clone();
+                    entity.offset = start - offset;
+                    entity.length = end - start;
+                    Some(entity)
+                })
+                .collect();
+
+            (text[start..end].to_string(), entities)
+        })
+        .collect()
+}
+
+/// Cuts a text to at most `limit` UTF-16 code units. An entity which is cut is closed at the end.
+pub fn trim(text: &str, mode: ParseMode, limit: usize) -> String {
+    split(text, mode, limit).into_iter().next().unwrap_or_default()
+}
+
+/// Calculates for each byte position which entities are open there. A position refers to an
+/// index into the returned list of delimiters, which have to be reopened at the beginning and
+/// closed at the end of a chunk, or is None if the text can't be cut there. The first element of
+/// the list belongs to the positions outside of any entity.
+fn markup_points(text: &str, mode: &ParseMode) -> (Vec<Option<usize>>, Vec<(String, String)>) {
+    let mut points = vec![None; text.len() + 1];
+    let mut tags = vec![(String::new(), String::new())];
+
+    // the opening and closing delimiters of the currently opened entities
+    let mut open: Vec<(&str, String)> = Vec::new();
+    // HTML tags and character references like &amp; can't be split either
+    let mut in_tag = false;
+    let mut in_reference = false;
+    // a cut directly after an opening delimiter would leave an empty entity
+    let mut opened = false;
+    // the number of characters which belong to the previous delimiter
+    let mut skip = 0;
+
+    for (idx, ch) in text.char_indices() {
+        if skip > 0 {
+            skip -= 1;
+            continue;
+        }
+
+        // the url of a link is only known at its end, so links can't be reopened
+        let in_link = open.iter().any(|&(_, ref close)| close == "]" || close == ")");
+
+        if !in_tag && !in_reference {
+            if opened {
+                opened = false;
+            } else if open.is_empty() {
+                points[idx] = Some(0);
+            } else if !in_link {
+                let reopen = open.iter().map(|&(tag, _)| tag).collect::<String>();
+                let close = open.iter().rev().map(|&(_, ref tag)| tag.as_str()).collect::<String>();
+
+                if tags.last() != Some(&(reopen.clone(), close.clone())) {
+                    tags.push((reopen, close));
+                }
+                points[idx] = Some(tags.len() - 1);
+            }
+        }
+
+        let rest = &text[idx..];
+
+        match *mode {
+            ParseMode::HTML => match ch {
+                '<' if rest.starts_with("</") => {
+                    open.pop();
+                    in_tag = true;
+                }
+                '<' => {
+                    let tag = &rest[..rest.find('>').map(|end| end + 1).unwrap_or(rest.len())];
+                    let name = tag[1..]
+                        .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+                        .next()
+                        .unwrap_or_default();
+
+                    open.push((tag, format!("</{}>", name)));
+                    in_tag = true;
+                    opened = true;
+                }
+                '>' => in_tag = false,
+                '&' => in_reference = true,
+                ';' => in_reference = false,
+                _ => {}
+            },
+            ParseMode::Markdown | ParseMode::MarkdownV2 => {
+                let top = open.last().map(|&(_, ref close)| close.clone()).unwrap_or_default();
+                let in_code = top == "`" || top == "```";
+
+                if ch == '\\' && (*mode == ParseMode::MarkdownV2 || !in_code) {
+                    // escaped characters have no special meaning
+                    skip = 1;
+                } else if top == ")" {
+                    // the url of a link ends with the first parenthesis
+                    if ch == ')' {
+                        open.pop();
+                    }
+                } else if top == "]" && rest.starts_with("](") {
+                    open.pop();
+                    open.push(("](", ")".into()));
+                    skip = 1;
+                } else {
+                    let delimiter = if rest.starts_with("```") {
+                        Some("```")
+                    } else if ch == '`' {
+                        Some("`")
+                    } else if in_code {
+                        None
+                    } else if *mode == ParseMode::MarkdownV2 && rest.starts_with("__") {
+                        Some("__")
+                    } else if *mode == ParseMode::MarkdownV2 && rest.starts_with("||") {
+                        Some("||")
+                    } else if ch == '*' || ch == '_'
+                        || (*mode == ParseMode::MarkdownV2 && ch == '~')
+                    {
+                        Some(&rest[..1])
+                    } else if ch == '[' {
+                        Some("[")
+                    } else {
+                        None
+                    };
+
+                    if let Some(delimiter) = delimiter {
+                        let tag = if top == delimiter {
+                            open.pop();
+                            delimiter
+                        } else if delimiter == "```" {
+                            opened = true;
+                            // the language of a code block belongs to its opening delimiter
+                            let tag = match rest.find('\n') {
+                                Some(end) if !rest[3..end].contains('`') => &rest[..end + 1],
+                                _ => delimiter,
+                            };
+
+                            open.push((tag, delimiter.into()));
+                            tag
+                        } else if delimiter == "[" {
+                            open.push((delimiter, "]".into()));
+                            delimiter
+                        } else {
+                            open.push((delimiter, delimiter.into()));
+                            opened = true;
+                            delimiter
+                        };
+
+                        skip = tag.chars().count() - 1;
+                    }
+                }
+            }
+            ParseMode::Text | ParseMode::Custom(_) => {}
+        }
+    }
+
+    points[text.len()] = Some(0);
+
+    (points, tags)
+}
+
+/// Splits the text greedily into ranges of at most `limit` UTF-16 code units. `points` refers for
+/// each byte position to the length of the delimiters which are reopened at the beginning and
+/// closed at the end of a range in `lengths`, or is None if the text can't be cut there.
+fn split_ranges(
+    text: &str,
+    points: &[Option<usize>],
+    lengths: &[(usize, usize)],
+    limit: usize,
+) -> Vec<(usize, usize)> {
+    // the number of UTF-16 code units in front of each character
+    let mut units = vec![0; text.len() + 1];
+    for (idx, ch) in text.char_indices() {
+        units[idx + ch.len_utf8()] = units[idx] + ch.len_utf16();
+    }
+
+    let mut ranges = Vec::new();
+    let mut start = 0;
+
+    loop {
+        // separators at the beginning of a chunk are dropped
+        while let Some(ch) = text[start..].chars().next() {
+            if ch.is_whitespace() && points[start] == Some(0) {
+                start += ch.len_utf8();
+            } else {
+                break;
+            }
+        }
+
+        if start >= text.len() {
+            break;
+        }
+
+        let limit = limit.saturating_sub(points[start].map(|idx| lengths[idx].0).unwrap_or(0));
+
+        // find the last byte position which fits into the limit
+        let max_end = text[start..]
+            .char_indices()
+            .map(|(idx, ch)| (start + idx, ch))
+            .find(|&(idx, ch)| units[idx] + ch.len_utf16() - units[start] > limit)
+            .map(|(idx, _)| idx)
+            .unwrap_or(text.len());
+
+        if max_end == text.len() {
+            ranges.push((start, max_end));
+            break;
+        }
+
+        // whether the text can be cut at the position without or with breaking an entity
+        let fits = |idx: usize, safe: bool| match points[idx] {
+            Some(point) => {
+                idx > start && (point == 0) == safe
+                    && units[idx] - units[start] + lengths[point].1 <= limit
+            }
+            None => false,
+        };
+        let window = &text[start..max_end];
+        let separator = |safe: bool| {
+            ["\n\n", "\n", " "]
+                .iter()
+                .filter_map(|sep| {
+                    window
+                        .rmatch_indices(sep)
+                        .map(|(idx, _)| start + idx)
+                        .find(|&idx| fits(idx, safe))
+                        .map(|idx| (idx, idx + sep.len()))
+                })
+                .next()
+        };
+        let last = |safe: bool| {
+            (start + 1..max_end + 1)
+                .rev()
+                .find(|&idx| fits(idx, safe))
+                .map(|idx| (idx, idx))
+        };
+
+        let (end, next) = separator(true)
+            .or_else(|| last(true))
+            .or_else(|| separator(false))
+            .or_else(|| last(false))
+            .unwrap_or((max_end, max_end));
+
+        // a single character which exceeds the limit can't be split
+        let (end, next) = if end == start {
+            let end = start + text[start..].chars().next().map(|c| c.len_utf8()).unwrap_or(1);
+            (end, end)
+        } else {
+            (end, next)
+        };
+
+        ranges.push((start, end));
+        start = next;
+    }
+
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(kind: MessageEntityKind, offset: i64, length: i64) -> MessageEntity {
+        MessageEntity {
+            kind: kind,
+            offset: offset,
+            length: length,
+            url: None,
+            user: None,
+        }
+    }
+
+    #[test]
+    fn utf16_offsets_with_surrogate_pairs() {
+        let text = "a😀b";
+
+        assert_eq!(utf16_to_byte(text, 0), Some(0));
+        assert_eq!(utf16_to_byte(text, 1), Some(1));
+        // the emoji is a surrogate pair, the offset 2 points into its middle
+        assert_eq!(utf16_to_byte(text, 2), None);
+        assert_eq!(utf16_to_byte(text, 3), Some(5));
+        assert_eq!(utf16_to_byte(text, 4), Some(6));
+        assert_eq!(utf16_to_byte(text, 5), None);
+    }
+
+    #[test]
+    fn entity_after_emoji() {
+        let text = "😀 bold";
+        let bold = entity(MessageEntityKind::Bold, 3, 4);
+
+        assert_eq!(entity_text(text, &bold), Some("bold"));
+        assert_eq!(render(text, &[bold], ParseMode::HTML), "😀 <b>bold</b>");
+    }
+
+    #[test]
+    fn split_never_breaks_surrogate_pairs() {
+        assert_eq!(split("😀😀😀", ParseMode::Text, 4), vec!["😀😀", "😀"]);
+        assert_eq!(split("😀😀😀", ParseMode::Text, 3), vec!["😀", "😀", "😀"]);
+    }
+
+    #[test]
+    fn split_whitespace_only() {
+        assert!(split("   \n\n  ", ParseMode::Text, 10).is_empty());
+        assert!(split_entities(" \n ", &[], 10).is_empty());
+        assert_eq!(trim("   ", ParseMode::Text, 10), "");
+    }
+
+    #[test]
+    fn split_prefers_paragraphs() {
+        let text = "first line\nsecond\n\nthird";
+
+        assert_eq!(split(text, ParseMode::Text, 20), vec!["first line\nsecond", "third"]);
+    }
+
+    #[test]
+    fn split_entity_longer_than_limit() {
+        let text = "*aaaa bbbb*";
+        let chunks = split(text, ParseMode::Markdown, 6);
+
+        // the entity is closed at the end of the chunk and reopened in the next one
+        assert_eq!(chunks, vec!["*aaaa*", "*bbbb*"]);
+    }
+
+    #[test]
+    fn split_reopens_html_tags() {
+        let text = "<pre>x x x x x x x x</pre>";
+
+        assert_eq!(
+            split(text, ParseMode::HTML, 20),
+            vec!["<pre>x x x x x</pre>", "<pre>x x x</pre>"]
+        );
+        assert_eq!(
+            split("<a href=\"u\"><b>aaaa bbbb</b></a>", ParseMode::HTML, 28),
+            vec!["<a href=\"u\"><b>aaaa</b></a>", "<a href=\"u\"><b>bbbb</b></a>"]
+        );
+        assert_eq!(trim("<b>aaaa bbbb cccc</b>", ParseMode::HTML, 10), "<b>aaa</b>");
+        assert_eq!(trim("<b>aaaa bbbb cccc</b>", ParseMode::HTML, 15), "<b>aaaa</b>");
+    }
+
+    #[test]
+    fn split_reopens_code_blocks_with_language() {
+        let text = "```rust\nlet a = 1;\nlet b = 2;\n```";
+
+        assert_eq!(
+            split(text, ParseMode::MarkdownV2, 24),
+            vec!["```rust\nlet a = 1;```", "```rust\nlet b = 2;\n```"]
+        );
+    }
+
+    #[test]
+    fn split_entities_cuts_long_entity() {
+        let chunks = split_entities("aaaa bbbb", &[entity(MessageEntityKind::Bold, 0, 9)], 5);
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].0, "aaaa ");
+        assert_eq!((chunks[0].1[0].offset, chunks[0].1[0].length), (0, 5));
+        assert_eq!(chunks[1].0, "bbbb");
+        assert_eq!((chunks[1].1[0].offset, chunks[1].1[0].length), (0, 4));
+    }
+
+    #[test]
+    fn split_entities_rebases_offsets() {
+        let text = "😀 first\n\nsecond bold";
+        let chunks = split_entities(text, &[entity(MessageEntityKind::Bold, 17, 4)], 12);
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].0, "😀 first");
+        assert!(chunks[0].1.is_empty());
+        assert_eq!(chunks[1].0, "second bold");
+        assert_eq!((chunks[1].1[0].offset, chunks[1].1[0].length), (7, 4));
+        assert_eq!(entity_text(&chunks[1].0, &chunks[1].1[0]), Some("bold"));
+    }
+
+    #[test]
+    fn html_character_references() {
+        assert_eq!(
+            render("a<b & \"c\"", &[], ParseMode::HTML),
+            "a&lt;b &amp; &quot;c&quot;"
+        );
+        // a character reference is never split
+        assert_eq!(split("a &amp; b", ParseMode::HTML, 5), vec!["a", "&amp;", "b"]);
+    }
+
+    #[test]
+    fn html_tags_are_not_split() {
+        assert_eq!(
+            split("<b>aa bb</b> cc", ParseMode::HTML, 12),
+            vec!["<b>aa bb</b>", "cc"]
+        );
+    }
+
+    #[test]
+    fn escaped_markdown_delimiters() {
+        // the escaped underscore doesn't open an entity, so the text can be split at the space
+        assert_eq!(split("\\_a b", ParseMode::Markdown, 4), vec!["\\_a", "b"]);
+        assert_eq!(escape("a_b*c", ParseMode::Markdown), "a\\_b\\*c");
+        assert_eq!(render("a.b", &[], ParseMode::MarkdownV2), "a\\.b");
+    }
+
+    #[test]
+    fn legacy_markdown_interrupts_entities() {
+        let italic = entity(MessageEntityKind::Italic, 0, 10);
+
+        assert_eq!(render("snake_case", &[italic], ParseMode::Markdown), "_snake_\\__case_");
+    }
+
+    #[test]
+    fn legacy_markdown_drops_nested_entities() {
+        let entities = [
+            entity(MessageEntityKind::Italic, 0, 8),
+            entity(MessageEntityKind::Bold, 3, 2),
+        ];
+
+        assert_eq!(render("an in it", &entities, ParseMode::Markdown), "_an in it_");
+        assert_eq!(render("an in it", &entities, ParseMode::MarkdownV2), "_an *in* it_");
+    }
+
+    #[test]
+    fn markdown_v2_separates_italic_from_underline() {
+        let entities = [
+            entity(MessageEntityKind::Underline, 0, 2),
+            entity(MessageEntityKind::Italic, 0, 2),
+        ];
+        let italics = [
+            entity(MessageEntityKind::Italic, 0, 1),
+            entity(MessageEntityKind::Italic, 1, 1),
+        ];
+
+        assert_eq!(render("ab", &entities, ParseMode::MarkdownV2), "___ab_\r__");
+        assert_eq!(render("ab", &italics, ParseMode::MarkdownV2), "_a_\r_b_");
+    }
+
+    #[test]
+    fn legacy_markdown_link_with_parenthesis() {
+        let mut link = entity(MessageEntityKind::TextLink, 0, 4);
+        link.url = Some("http://a.b/(x)".into());
+
+        assert_eq!(render("link", &[link], ParseMode::Markdown), "[link](http://a.b/(x%29)");
+    }
+
+    #[test]
+    fn code_is_not_escaped_in_legacy_markdown() {
+        let code = entity(MessageEntityKind::Code, 0, 3);
+
+        assert_eq!(render("a_b", &[code], ParseMode::Markdown), "`a_b`");
+    }
+}
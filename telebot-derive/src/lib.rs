@@ -1,6 +1,6 @@
     #![feature(try_from)]
     #![feature(proc_macro, proc_macro_lib)]
-    #![recursion_limit="150"]
+    #![recursion_limit="256"]
 
     extern crate log;
     extern crate proc_macro;
@@ -173,6 +173,18 @@ fn expand_function(ast: syn::MacroInput) -> quote::Tokens {
         }
     };
 
+    // chat ids can be either an integer or a channel name and actions can be parsed from a string,
+    // therefore these arguments are generic
+    let is_generic = |ref f: &(&syn::Ident, &syn::Ty)| -> bool {
+        match *f.1 {
+            syn::Ty::Path(_, ref path) => match path.segments.last().unwrap().ident.as_ref() {
+                "ChatId" | "Action" => true,
+                _ => false
+            },
+            _ => false
+        }
+    };
+
     let field_compulsory: Vec<_> = fields.iter().filter(|f| !is_option_ident(&f))
         .map(|f| syn::Ident::from(format!("_{}", f.0.as_ref()))).collect();
 
@@ -181,19 +193,38 @@ fn expand_function(ast: syn::MacroInput) -> quote::Tokens {
 
     let field_compulsory2: Vec<_> = fields.iter().map(|f| f.0).collect();
     let field_compulsory3 = field_compulsory.clone();
-    let values: Vec<_> = fields.iter().map(|f| {
-        match *f.1 {
-            syn::Ty::Path(_, ref path) => {
-                match path.segments.first().unwrap().ident.as_ref() {
-                    "Option" => return syn::Ident::from("None"),
-                    _ => return syn::Ident::from(format!("_{}", f.0.as_ref()))
-                }
-            },
-            _ => return syn::Ident::from("None")
+    let values: Vec<quote::Tokens> = fields.iter().map(|f| {
+        let ident = syn::Ident::from(format!("_{}", f.0.as_ref()));
+
+        if is_option_ident(f) {
+            quote! { None }
+        } else if is_generic(f) {
+            quote! { #ident.into() }
+        } else {
+            quote! { #ident }
         }
     }).collect();
 
-    let ty_compulsory: Vec<_> = fields.iter().map(|f| f.1).collect();
+    let generics: Vec<_> = fields.iter().filter(|f| !is_option_ident(&f)).filter(|f| is_generic(&f))
+        .map(|f| {
+            let param = type_param(f.0);
+            let ty = f.1;
+
+            quote! { #param: Into<#ty> }
+        }).collect();
+    let generics2 = generics.clone();
+
+    let ty_compulsory: Vec<_> = fields.iter().filter(|f| !is_option_ident(&f)).map(|f| {
+        if is_generic(f) {
+            let param = type_param(f.0);
+
+            quote! { #param }
+        } else {
+            let ty = f.1;
+
+            quote! { #ty }
+        }
+    }).collect();
     let ty_compulsory2 = ty_compulsory.clone();
     let ty_optional: Vec<_> = fields.iter().filter(|f| is_option_ident(&f)).map(|f| {
         if let syn::Ty::Path(_, ref path) = *f.1 {
@@ -212,156 +243,218 @@ fn expand_function(ast: syn::MacroInput) -> quote::Tokens {
     let trait_name = syn::Ident::from(format!("Function{}",  name.as_ref()));
     let wrapper_name = syn::Ident::from(format!("Wrapper{}", name.as_ref()));
 
-    let tokens = quote! {
+    // the main file of the function, which can be given as an url, a file id or an upload
+    let file_methods = match file_kind {
+        Some(file_kind) => {
+            let file_kind_name = syn::Lit::Str(format!("{}", file_kind), syn::StrStyle::Cooked);
+            let file_kind2 = file_kind.clone();
+
+            quote! {
+                pub fn url<S>(mut self, val: S) -> Self where S: Into<String> {
+                    self.inner.#file_kind = Some(val.into());
+
+                    self
+                }
+
+                pub fn file_id<S>(mut self, val: S) -> Self where S: Into<String> {
+                    self.inner.#file_kind2 = Some(val.into());
+
+                    self
+                }
+
+                pub fn file<S>(self, val: S) -> Self where S: TryInto<file::File> {
+                    self.attach(#file_kind_name, val)
+                }
+            }
+        },
+        None => quote! {}
+    };
+
+    // additional files (e.g. thumbnails) are uploaded as attachments and referenced by attach://<name>
+    let attach_fields: Vec<_> = config.get("attach")
+        .map(|fields| fields.split(',').map(|x| x.trim().to_string()).collect())
+        .unwrap_or(Vec::new());
+    let attach_methods: Vec<_> = attach_fields.iter().map(|field| {
+        let method = syn::Ident::from(format!("{}_file", field));
+        let field_ident = syn::Ident::from(field.as_str());
+        let field_name = syn::Lit::Str(field.clone(), syn::StrStyle::Cooked);
+        let reference = syn::Lit::Str(format!("attach://{}", field), syn::StrStyle::Cooked);
+
+        quote! {
+            pub fn #method<S>(mut self, val: S) -> Self where S: TryInto<file::File> {
+                self.inner.#field_ident = Some(#reference.into());
+
+                self.attach(#field_name, val)
+            }
+        }
+    }).collect();
+
+    quote! {
         #[allow(dead_code)]
         pub struct #wrapper_name {
             bot: Rc<Bot>,
             inner: #name,
-            file: Option<Result<file::File, Error>>
+            files: Vec<(String, Result<file::File, Error>)>
         }
-    };
 
-    if let Some(file_kind) = file_kind {
-        let file_kind_name = syn::Lit::Str(format!("{}", file_kind), syn::StrStyle::Cooked);
-        quote! {
-            #tokens
+        pub trait #trait_name {
+             fn #bot_function<#( #generics ),*>(&self, #( #field_compulsory: #ty_compulsory, )*) -> #wrapper_name;
+        }
 
-            pub trait #trait_name {
-                 fn #bot_function(&self, #( #field_compulsory: #ty_compulsory, )*) -> #wrapper_name;
+        impl #trait_name for RcBot {
+            fn #bot_function<#( #generics2 ),*>(&self, #( #field_compulsory3: #ty_compulsory2, )*) -> #wrapper_name {
+                #wrapper_name { inner: #name { #( #field_compulsory2: #values, )* }, bot: self.inner.clone(), files: Vec::new() }
             }
+        }
 
-            impl #trait_name for RcBot {
-                fn #bot_function(&self, #( #field_compulsory3: #ty_compulsory2, )*) -> #wrapper_name {
-                    #wrapper_name { inner: #name { #( #field_compulsory2: #values, )* }, bot: self.inner.clone(), file: None }
-                }
+        impl #wrapper_name {
+            pub fn send<'a>(self) -> impl Future<Item=(RcBot, objects::#answer), Error=Error> + 'a {
+                use futures::future::{result, Either};
+
+                let #wrapper_name { bot, inner, files } = self;
+                let cloned_bot = bot.clone();
+
+                // without files the request is sent as JSON, which is more compact
+                let request = files.into_iter()
+                    .map(|(name, file)| file.map(|file| (name, file)))
+                    .collect::<Result<Vec<(String, file::File)>, Error>>()
+                    .and_then(|files| {
+                        serde_json::to_value(&inner)
+                            .map(|msg| (msg, files))
+                            .map_err(|e| Error::from(e.context(ErrorKind::JsonSerialize)))
+                    });
+
+                result(request)
+                    .and_then(move |(msg, files)| {
+                        if files.is_empty() {
+                            Either::A(bot.fetch_json(#function, &msg.to_string()))
+                        } else {
+                            Either::B(bot.fetch_formdata(#function, &msg, files))
+                        }
+                    })
+                    .and_then(move |answer| {
+                        let bot = RcBot { inner: cloned_bot };
+
+                        serde_json::from_str::<objects::#answer>(&answer)
+                            .map(|json| (bot, json))
+                            .map_err(|x| Error::from(x.context(ErrorKind::JsonParse)))
+                    })
             }
-            impl #wrapper_name {
-                pub fn send<'a>(self) -> impl Future<Item=(RcBot, objects::#answer), Error=Error> + 'a{
-                    use futures::future::result;
-                    use futures::IntoFuture;
-
-                    let cloned_bot = self.bot.clone();
-
-                    result::<#wrapper_name, Error>(Ok(self))
-                        .and_then(move |mut tmp| {
-                            match serde_json::to_value(&tmp.inner) {
-                                Ok(msg) => {
-                                    if let Some(file) = tmp.file.take() {
-                                        match file {
-                                            Ok(file) => Ok((tmp, msg, Some(file))),
-                                            Err(e) => Err(e)
-                                        }
-                                    } else {
-                                        return Ok((tmp, msg, None));
-                                    }
-                                },
-                                Err(err) => Err(Error::from(err.context(ErrorKind::JsonSerialize)))
-                            }
-                        })
-                        .and_then(move |(tmp, msg, file)| {
-                            let bot = tmp.bot.clone();
-                            let bot2 = tmp.bot.clone();
-                            let msg_str = serde_json::to_string(&msg).unwrap();
-
-                            file.ok_or(Error::from(ErrorKind::NoFile)).into_future()
-                                .and_then(move |file| {
-                                    bot.fetch_formdata(#function, &msg, file, #file_kind_name)
-                                })
-                                .or_else(move |_| {
-                                    bot2.fetch_json(#function, &msg_str)
-                                })
-                        })
-                        .and_then(move |answer| {
-                            let bot = RcBot { inner: cloned_bot };
-
-                            serde_json::from_str::<objects::#answer>(&answer)
-                                .map(|json| (bot, json))
-                                .map_err(|x| Error::from(x.context(ErrorKind::JsonParse)))
-                        })
-                }
 
-                #(
-                    pub fn #field_optional<S>(mut self, val: S) -> Self where S: Into<#ty_optional> {
-                        self.inner.#field_optional2 = Some(val.into());
+            // uploads a file as the form field `name`, other fields can reference it with attach://<name>
+            pub fn attach<S>(mut self, name: &str, val: S) -> Self where S: TryInto<file::File> {
+                let file = val.try_into().map_err(|_| Error::from(ErrorKind::NoFile));
 
-                        self
-                    }
-                )*
+                self.files.retain(|&(ref x, _)| x != name);
+                self.files.push((name.into(), file));
 
-                pub fn url<S>(mut self, val: S) -> Self where S: Into<String> {
-                    self.inner.#file_kind = Some(val.into());
+                self
+            }
+
+            #(
+                pub fn #field_optional<S>(mut self, val: S) -> Self where S: Into<#ty_optional> {
+                    self.inner.#field_optional2 = Some(val.into());
 
                     self
                 }
+            )*
 
-                pub fn file_id<S>(mut self, val: S) -> Self where S: Into<String> {
-                    self.inner.#file_kind = Some(val.into());
+            #file_methods
 
-                    self
-                }
+            #( #attach_methods )*
+        }
+    }
+}
 
-                pub fn file<S>(mut self, val: S) -> Self where S: TryInto<file::File> {
-                    match val.try_into() {
-                        Ok(val) => {
-                            self.file = Some(Ok(val));
+#[proc_macro_derive(CallbackData, attributes(callback))]
+pub fn derive_callback_data(input: TokenStream) -> TokenStream {
+    let ast = syn::parse_macro_input(&input.to_string()).unwrap();
+    let expanded = expand_callback_data(ast);
+    expanded.to_string().parse().unwrap()
+}
 
-                            self
-                        },
-                        Err(_) => {
-                            self.file = Some(Err(Error::from(ErrorKind::NoFile)));
+fn expand_callback_data(ast: syn::MacroInput) -> quote::Tokens {
+    let config = config_from(&ast.attrs);
+    let name = &ast.ident;
 
-                            self
-                        },
-                    }
-                }
-            }
-        }
+    // the tag identifies the type in the callback data and defaults to the name of the struct
+    let tag = config.get("callback").cloned().unwrap_or_else(|| name.as_ref().to_string());
+    if tag.contains('|') {
+        panic!("#[callback = \"{}\"]: the tag of callback data may not contain '|'", tag);
+    }
+    let tag = syn::Lit::Str(tag, syn::StrStyle::Cooked);
+    let tag2 = tag.clone();
+
+    let (fields, is_unit): (Vec<_>, bool) = match ast.body {
+        syn::Body::Struct(syn::VariantData::Struct(ref fields)) => {
+            (fields.iter().map(|f| (f.ident.as_ref().unwrap(), &f.ty)).collect(), false)
+        },
+        syn::Body::Struct(syn::VariantData::Unit) => {
+            (vec![], true)
+        },
+        _ => panic!("#[derive(CallbackData)] can only be used with braced structs"),
+    };
+
+    let field_names: Vec<_> = fields.iter().map(|f| f.0).collect();
+    let field_names2 = field_names.clone();
+    let field_names3 = field_names.clone();
+    let field_names4 = field_names.clone();
+    let field_types: Vec<_> = fields.iter().map(|f| f.1).collect();
+
+    let value = if is_unit {
+        quote! { #name }
     } else {
-        quote! {
-            #tokens
+        quote! { #name { #( #field_names3: #field_names4, )* } }
+    };
 
-            pub trait #trait_name {
-                 fn #bot_function(&self, #( #field_compulsory: #ty_compulsory, )*) -> #wrapper_name;
-            }
+    // an empty tuple is serialized as null, therefore an empty array is used without fields
+    if fields.is_empty() {
+        return quote! {
+            impl ::telebot::callback::CallbackData for #name {
+                fn encode(&self) -> Result<String, ::telebot::callback::Error> {
+                    ::telebot::callback::encode_fields(#tag, &[0u8; 0])
+                }
+
+                fn decode(data: &str) -> Result<Self, ::telebot::callback::Error> {
+                    let _: [u8; 0] = ::telebot::callback::decode_fields(#tag2, data)?;
 
-            impl #trait_name for RcBot {
-                fn #bot_function(&self, #( #field_compulsory3: #ty_compulsory2, )*) -> #wrapper_name {
-                    #wrapper_name { inner: #name { #( #field_compulsory2: #values, )* }, bot: self.inner.clone(), file: None }
+                    Ok(#value)
                 }
             }
-            impl #wrapper_name {
-                pub fn send<'a>(self) -> impl Future<Item=(RcBot, objects::#answer), Error=Error> + 'a{
-                    use futures::future::result;
-                    result(serde_json::to_string(&self.inner))
-                        .map_err(|e| Error::from(e.context(ErrorKind::JsonSerialize)))
-                        .and_then(move |msg| {
-                            let obj = self.bot.fetch_json(#function, &msg)
-                                .and_then(move |x| {
-                                    let bot = RcBot {
-                                        inner: self.bot.clone(),
-                                    };
-
-                                    serde_json::from_str::<objects::#answer>(&x)
-                                        .map(|json| (bot, json))
-                                        .map_err(|x| Error::from(x.context(ErrorKind::JsonParse)))
-                                });
-
-                            Box::new(obj)
-                        })
-                }
+        };
+    }
+
+    quote! {
+        impl ::telebot::callback::CallbackData for #name {
+            fn encode(&self) -> Result<String, ::telebot::callback::Error> {
+                ::telebot::callback::encode_fields(#tag, &( #( &self.#field_names, )* ))
+            }
 
-                #(
-                    pub fn #field_optional<S>(mut self, val: S) -> Self where S: Into<#ty_optional> {
-                        self.inner.#field_optional2 = Some(val.into());
+            fn decode(data: &str) -> Result<Self, ::telebot::callback::Error> {
+                let ( #( #field_names2, )* ): ( #( #field_types, )* ) =
+                    ::telebot::callback::decode_fields(#tag2, data)?;
 
-                        self
-                    }
-                )*
+                Ok(#value)
             }
         }
     }
 }
 
+/// Converts a field name like chat_id into a type parameter like TChatId
+fn type_param(field: &syn::Ident) -> syn::Ident {
+    let name: String = field.as_ref().split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new()
+            }
+        })
+        .collect();
+
+    syn::Ident::from(format!("T{}", name))
+}
+
 fn config_from(attrs: &[syn::Attribute]) -> BTreeMap<String, String> {
     let mut result = BTreeMap::new();
     for attr in attrs {